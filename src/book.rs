@@ -0,0 +1,442 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::review_log::ReviewRecord;
+use crate::scheduler::{Grade, Leitner, ReviewState, Scheduler};
+
+// what is being asked about the kanji, every mode is scheduled independently.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QuizMode {
+    Meaning,
+    Reading,
+    Nanori,
+    Radical,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    pub kanji: char,
+    pub confidence_level: i32, // 0 - 5?
+    // meaning -> kanji
+    #[serde(default)]
+    pub schedule: ReviewState,
+    // kanji -> reading
+    #[serde(default)]
+    pub reading_schedule: ReviewState,
+    // kanji -> name reading
+    #[serde(default)]
+    pub nanori_schedule: ReviewState,
+    // kanji -> radical
+    #[serde(default)]
+    pub radical_schedule: ReviewState,
+    // place in the textbook the deck follows, earlier ones get asked first when equally due.
+    #[serde(default)]
+    pub position: Option<u32>,
+    // leitner sessions keep their own timing, apart from the spaced repetition schedules.
+    #[serde(default)]
+    pub leitner_last_review: Option<u64>,
+}
+
+impl Entry {
+    pub fn new(kanji: char) -> Self {
+        Entry {
+            kanji,
+            confidence_level: 0,
+            schedule: ReviewState::default(),
+            reading_schedule: ReviewState::default(),
+            nanori_schedule: ReviewState::default(),
+            radical_schedule: ReviewState::default(),
+            position: None,
+            leitner_last_review: None,
+        }
+    }
+
+    pub fn schedule(&self, mode: QuizMode) -> &ReviewState {
+        match mode {
+            QuizMode::Meaning => &self.schedule,
+            QuizMode::Reading => &self.reading_schedule,
+            QuizMode::Nanori => &self.nanori_schedule,
+            QuizMode::Radical => &self.radical_schedule,
+        }
+    }
+
+    fn schedule_mut(&mut self, mode: QuizMode) -> &mut ReviewState {
+        match mode {
+            QuizMode::Meaning => &mut self.schedule,
+            QuizMode::Reading => &mut self.reading_schedule,
+            QuizMode::Nanori => &mut self.nanori_schedule,
+            QuizMode::Radical => &mut self.radical_schedule,
+        }
+    }
+
+    // confidence (and the leitner box) follows the original meaning quiz only.
+    fn update_confidence(&mut self, mode: QuizMode, grade: Grade) {
+        if mode == QuizMode::Meaning {
+            match grade {
+                Grade::Good => self.confidence_level += 1,
+                Grade::Hard => {}
+                Grade::Again => self.confidence_level = 0,
+            }
+        }
+    }
+
+    pub fn apply_grade(
+        &mut self,
+        mode: QuizMode,
+        grade: Grade,
+        now: u64,
+        scheduler: &dyn Scheduler,
+    ) {
+        self.update_confidence(mode, grade);
+        let schedule = self.schedule_mut(mode);
+        *schedule = scheduler.review(schedule, grade, now);
+    }
+}
+
+pub struct Book {
+    pub kanjis: BTreeMap<char, Entry>,
+    scheduler: Box<dyn Scheduler>,
+}
+
+impl Book {
+    pub fn new(kanjis: BTreeMap<char, Entry>, scheduler: Box<dyn Scheduler>) -> Self {
+        Book { kanjis, scheduler }
+    }
+
+    pub fn load(file_name: &str, scheduler: Box<dyn Scheduler>) -> Self {
+        let kanjis = if Path::new(file_name).exists() {
+            serde_json::from_reader(std::fs::File::open(file_name).expect("Couldnt open file"))
+                .unwrap_or_else(|e| panic!("Unable to parse out dict from {}: {}", file_name, e))
+        } else {
+            BTreeMap::new()
+        };
+
+        Book::new(kanjis, scheduler)
+    }
+
+    pub fn set_scheduler(&mut self, scheduler: Box<dyn Scheduler>) {
+        self.scheduler = scheduler;
+    }
+
+    // picks the card that has been waiting the longest past its due time, None if nothing is due yet.
+    // only kanjis that are eligible (e.g. match the quiz filter) are considered.
+    pub fn roll(&self, mode: QuizMode, now: u64, eligible: impl Fn(char) -> bool) -> Option<char> {
+        self.kanjis
+            .values()
+            .filter(|e| e.schedule(mode).is_due(now) && eligible(e.kanji))
+            .min_by_key(|e| (e.schedule(mode).due, e.position.unwrap_or(u32::MAX)))
+            .map(|e| e.kanji)
+    }
+
+    // grades the answer and returns what happened, for the review log.
+    pub fn grade(
+        &mut self,
+        kanji: char,
+        mode: QuizMode,
+        grade: Grade,
+        now: u64,
+        response_ms: u64,
+    ) -> ReviewRecord {
+        let entry = self
+            .kanjis
+            .get_mut(&kanji)
+            .expect("graded kanji that is not in the book");
+        let before = entry.schedule(mode).clone();
+        entry.apply_grade(mode, grade, now, self.scheduler.as_ref());
+
+        ReviewRecord {
+            kanji,
+            timestamp: now,
+            mode,
+            grade,
+            response_ms,
+            before,
+            after: entry.schedule(mode).clone(),
+            leitner: false,
+        }
+    }
+
+    // a leitner answer only moves the card between boxes, no scheduler runs.
+    pub fn grade_leitner(
+        &mut self,
+        kanji: char,
+        grade: Grade,
+        now: u64,
+        response_ms: u64,
+    ) -> ReviewRecord {
+        let entry = self
+            .kanjis
+            .get_mut(&kanji)
+            .expect("graded kanji that is not in the book");
+        entry.update_confidence(QuizMode::Meaning, grade);
+        entry.leitner_last_review = Some(now);
+
+        ReviewRecord {
+            kanji,
+            timestamp: now,
+            mode: QuizMode::Meaning,
+            grade,
+            response_ms,
+            before: entry.schedule.clone(),
+            after: entry.schedule.clone(),
+            leitner: true,
+        }
+    }
+
+    // (cards, due cards) in every leitner box, boxes are ordered from the first one.
+    pub fn leitner_counts(&self, leitner: &Leitner, now: u64) -> Vec<(usize, usize)> {
+        let mut counts = vec![(0, 0); leitner.box_count()];
+        for e in self.kanjis.values() {
+            let count = &mut counts[leitner.box_of(e.confidence_level) - 1];
+            count.0 += 1;
+            if leitner.is_due(e.confidence_level, e.leitner_last_review, now) {
+                count.1 += 1;
+            }
+        }
+        counts
+    }
+
+    // every card of every due box, lowest boxes first.
+    pub fn leitner_due(
+        &self,
+        leitner: &Leitner,
+        now: u64,
+        eligible: impl Fn(char) -> bool,
+    ) -> Vec<char> {
+        let mut due: Vec<&Entry> = self
+            .kanjis
+            .values()
+            .filter(|e| {
+                leitner.is_due(e.confidence_level, e.leitner_last_review, now) && eligible(e.kanji)
+            })
+            .collect();
+        due.sort_by_key(|e| leitner.box_of(e.confidence_level));
+        due.into_iter().map(|e| e.kanji).collect()
+    }
+
+    // rebuilds review state of the whole book from the log. Schedules are taken as logged after
+    // the last review, so they stay what the scheduler of that time made of them.
+    // kanjis that are only known from the log are added back.
+    pub fn replay(&mut self, records: &[ReviewRecord]) {
+        for entry in self.kanjis.values_mut() {
+            *entry = Entry {
+                position: entry.position,
+                ..Entry::new(entry.kanji)
+            };
+        }
+
+        for r in records {
+            let entry = self
+                .kanjis
+                .entry(r.kanji)
+                .or_insert_with(|| Entry::new(r.kanji));
+            entry.update_confidence(r.mode, r.grade);
+            if r.leitner {
+                entry.leitner_last_review = Some(r.timestamp);
+            } else {
+                *entry.schedule_mut(r.mode) = r.after.clone();
+            }
+        }
+    }
+
+    pub fn add(&mut self, entry: Entry) {
+        self.kanjis.insert(entry.kanji, entry);
+    }
+
+    pub fn save(&self, file_name: &str) {
+        let serialized = serde_json::to_string(&self.kanjis).expect("Unable to serialize book!");
+        let mut f = std::fs::OpenOptions::new()
+            .truncate(true)
+            .write(true)
+            .create(true)
+            .open(file_name)
+            .expect("Couldnt open file for writing.");
+
+        f.write_all(serialized.as_bytes())
+            .expect("Couldnt write the book.");
+    }
+
+    // adds new entries for the kanjis not in the book yet, returns how many were added.
+    pub fn add_missing(&mut self, kanjis: &[char]) -> usize {
+        let missing = self.missing(kanjis);
+        for k in &missing {
+            self.add(Entry::new(*k));
+        }
+        missing.len()
+    }
+
+    // numbers the deck in the given order and adds up to add_up_to of the missing kanjis, first ones first.
+    // returns how many were added.
+    pub fn follow_order(&mut self, order: &[char], add_up_to: usize) -> usize {
+        // numbers of a textbook followed before dont mix with the new ones.
+        for entry in self.kanjis.values_mut() {
+            entry.position = None;
+        }
+        let mut added = 0;
+        for (position, k) in order.iter().enumerate() {
+            if !self.kanjis.contains_key(k) && added < add_up_to {
+                self.add(Entry::new(*k));
+                added += 1;
+            }
+            if let Some(entry) = self.kanjis.get_mut(k) {
+                entry.position = Some(position as u32);
+            }
+        }
+        added
+    }
+
+    pub fn missing(&self, kanjis: &[char]) -> Vec<char> {
+        kanjis
+            .iter()
+            .filter(|k| !self.kanjis.contains_key(k))
+            .copied()
+            .collect()
+    }
+
+    pub fn add_save(&mut self, entry: Entry, file_name: &str) {
+        self.add(entry);
+        self.save(file_name);
+    }
+}
+
+#[cfg(test)]
+mod book_test {
+    use super::*;
+    use crate::scheduler::{self, Fsrs, Sm2};
+
+    #[test]
+    fn roll_picks_most_overdue() {
+        let mut book = Book::new(BTreeMap::new(), Box::new(Sm2));
+        book.add(Entry::new('一'));
+        book.add(Entry::new('二'));
+        book.add(Entry::new('三'));
+
+        let now = 1_000_000;
+        let mode = QuizMode::Meaning;
+        book.grade('一', mode, Grade::Good, now, 0);
+        book.grade('二', mode, Grade::Again, now, 0);
+        book.kanjis.get_mut(&'三').unwrap().schedule.due = now - 10;
+
+        assert_eq!(book.roll(mode, now, |_| true), Some('三'));
+        assert_eq!(book.roll(mode, now, |k| k != '三'), None);
+        assert_eq!(book.kanjis[&'一'].confidence_level, 1);
+
+        book.grade('三', mode, Grade::Good, now, 0);
+        assert_eq!(book.roll(mode, now, |_| true), None);
+        assert_eq!(book.roll(QuizMode::Reading, now, |_| true), Some('一'));
+        assert_eq!(
+            book.roll(mode, now + scheduler::SECONDS_PER_DAY, |_| true),
+            Some('二')
+        );
+    }
+
+    #[test]
+    fn leitner_failures_drop_to_the_first_box() {
+        let mut book = Book::new(BTreeMap::new(), Box::new(Sm2));
+        book.add(Entry::new('一'));
+        book.add(Entry::new('二'));
+        let leitner = Leitner::default();
+        let day = scheduler::SECONDS_PER_DAY;
+        let mode = QuizMode::Meaning;
+
+        assert_eq!(book.leitner_due(&leitner, 0, |_| true), vec!['一', '二']);
+        book.grade_leitner('一', Grade::Good, 0, 0);
+        book.grade_leitner('一', Grade::Good, day, 0);
+        book.grade_leitner('二', Grade::Good, day, 0);
+        // the spaced repetition schedule is left alone, and its reviews dont restart the box.
+        assert_eq!(book.kanjis[&'一'].schedule, ReviewState::default());
+        book.grade('二', mode, Grade::Hard, 2 * day, 0);
+        assert_eq!(
+            book.leitner_counts(&leitner, day),
+            vec![(0, 0), (1, 0), (1, 0), (0, 0), (0, 0), (0, 0)]
+        );
+        assert_eq!(book.leitner_due(&leitner, 3 * day, |_| true), vec!['二']);
+        assert_eq!(
+            book.leitner_due(&leitner, 5 * day, |_| true),
+            vec!['二', '一']
+        );
+
+        book.grade_leitner('一', Grade::Again, 6 * day, 0);
+        assert_eq!(leitner.box_of(book.kanjis[&'一'].confidence_level), 1);
+    }
+
+    #[test]
+    fn add_missing_skips_known_kanjis() {
+        let mut book = Book::new(BTreeMap::new(), Box::new(Sm2));
+        book.add(Entry::new('一'));
+        book.grade('一', QuizMode::Meaning, Grade::Good, 0, 0);
+
+        assert_eq!(book.missing(&['一', '二', '三']), vec!['二', '三']);
+        assert_eq!(book.add_missing(&['一', '二', '三']), 2);
+        assert_eq!(book.kanjis.len(), 3);
+        assert_eq!(book.kanjis[&'一'].confidence_level, 1);
+        assert_eq!(book.add_missing(&['一', '二']), 0);
+    }
+
+    #[test]
+    fn follow_order_numbers_and_adds() {
+        let mut book = Book::new(BTreeMap::new(), Box::new(Sm2));
+        book.add(Entry::new('三'));
+
+        assert_eq!(book.follow_order(&['一', '二', '三', '四'], 1), 1);
+        assert_eq!(book.kanjis.len(), 2);
+        assert_eq!(book.kanjis[&'一'].position, Some(0));
+        assert_eq!(book.kanjis[&'三'].position, Some(2));
+        assert_eq!(book.roll(QuizMode::Meaning, 0, |_| true), Some('一'));
+
+        assert_eq!(book.follow_order(&['四', '三', '二', '一'], 0), 0);
+        assert_eq!(book.roll(QuizMode::Meaning, 0, |_| true), Some('三'));
+    }
+
+    #[test]
+    fn following_another_order_drops_the_old_numbers() {
+        let mut book = Book::new(BTreeMap::new(), Box::new(Sm2));
+        book.follow_order(&['一', '二', '三'], 3);
+        book.follow_order(&['三', '四'], 0);
+
+        assert_eq!(book.kanjis[&'一'].position, None);
+        assert_eq!(book.kanjis[&'二'].position, None);
+        assert_eq!(book.kanjis[&'三'].position, Some(0));
+        assert_eq!(book.roll(QuizMode::Meaning, 0, |_| true), Some('三'));
+    }
+
+    #[test]
+    fn replay_rebuilds_the_state() {
+        let mut book = Book::new(BTreeMap::new(), Box::new(Sm2));
+        book.add(Entry::new('一'));
+        book.add(Entry::new('二'));
+
+        let mode = QuizMode::Meaning;
+        let records = vec![
+            book.grade('一', mode, Grade::Good, 100, 0),
+            book.grade('二', mode, Grade::Again, 200, 0),
+            book.grade('一', mode, Grade::Good, 300, 0),
+        ];
+        let expected: Vec<_> = book.kanjis.values().cloned().collect();
+
+        // switching schedulers later doesnt rewrite the past.
+        let mut rebuilt = Book::new(BTreeMap::new(), Box::new(Fsrs::default()));
+        rebuilt.add(Entry::new('三'));
+        rebuilt.replay(&records);
+
+        assert_eq!(rebuilt.kanjis.len(), 3);
+        assert_eq!(rebuilt.kanjis[&'三'].schedule, ReviewState::default());
+        for e in expected {
+            assert_eq!(rebuilt.kanjis[&e.kanji].schedule, e.schedule);
+            assert_eq!(
+                rebuilt.kanjis[&e.kanji].confidence_level,
+                e.confidence_level
+            );
+        }
+    }
+
+    #[test]
+    fn old_entries_without_schedule_load() {
+        let old: BTreeMap<char, Entry> =
+            serde_json::from_str(r#"{"一":{"kanji":"一","confidence_level":7}}"#).unwrap();
+        assert_eq!(old[&'一'].schedule, ReviewState::default());
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::BufRead;
+
+pub const KRADFILE: &str = "kradfile.utf8";
+
+// KRADFILE lines look like '亜 : ｜ 一 口', '#' starts a comment line.
+// lines that are not utf-8 or not shaped like that are skipped, reading stops at an io error.
+pub fn parse_kradfile(reader: impl BufRead) -> BTreeMap<char, BTreeSet<char>> {
+    reader
+        .split(b'\n')
+        .map_while(Result::ok)
+        .filter_map(|line| String::from_utf8(line).ok())
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (kanji, components) = line.split_once(':')?;
+            let mut kanji = kanji.trim().chars();
+            match (kanji.next(), kanji.next()) {
+                (Some(kanji), None) => Some((
+                    kanji,
+                    components
+                        .split_whitespace()
+                        .filter_map(|c| c.chars().next())
+                        .collect(),
+                )),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// None when there is no KRADFILE, components get derived from KanjiVG then.
+pub fn load_kradfile() -> Option<BTreeMap<char, BTreeSet<char>>> {
+    let f = std::fs::File::open(KRADFILE).ok()?;
+    Some(parse_kradfile(std::io::BufReader::new(f)))
+}
+
+#[cfg(test)]
+mod components_test {
+    use super::*;
+
+    #[test]
+    fn kradfile() {
+        let kradfile = "# comment : with a colon\n亜 : ｜ 一 口\n唖 : ｜ 一 口\n\n";
+        let components = parse_kradfile(kradfile.as_bytes());
+
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[&'亜'].iter().collect::<String>(), "一口｜");
+    }
+
+    #[test]
+    fn kradfile_skips_bad_lines() {
+        let mut kradfile = "亜 : ｜ 一 口\n".as_bytes().to_vec();
+        // a euc-jp line in the utf-8 file and a line without a single kanji.
+        kradfile.extend_from_slice(b"\xb0\xa1 : \xa1\xc3\n");
+        kradfile.extend_from_slice("亜唖 : 口\n上 : ｜ 一 卜\r\n".as_bytes());
+        let components = parse_kradfile(kradfile.as_slice());
+
+        assert_eq!(components.keys().collect::<String>(), "上亜");
+        assert_eq!(components[&'上'].iter().collect::<String>(), "一卜｜");
+    }
+}
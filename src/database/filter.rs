@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+
+use super::Kanji;
+
+// narrows kanjis down by their KANJIDIC2 metadata, every unset criterion lets everything through.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct KanjiFilter {
+    // KANJIDIC2 still uses the old 4 levels of JLPT, 4 being the easiest one.
+    pub jlpt: Option<u32>,
+    // school grade, 1-6 elementary, 8 rest of jouyou, 9-10 jinmeiyou.
+    pub max_grade: Option<u32>,
+    // kanjis ranked up to this frequency.
+    pub max_freq: Option<u32>,
+    pub min_strokes: Option<u32>,
+    pub max_strokes: Option<u32>,
+}
+
+// modern N5-N1 levels onto the old 4 levels KANJIDIC2 has, N3 got split out of the old level 2.
+fn old_jlpt_level(n: u32) -> Option<u32> {
+    match n {
+        5 => Some(4),
+        4 => Some(3),
+        3 | 2 => Some(2),
+        1 => Some(1),
+        _ => None,
+    }
+}
+
+fn parse_number(term: &str, value: &str) -> Result<u32, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' in '{}' is not a number", value, term))
+}
+
+impl KanjiFilter {
+    // space separated criteria: 'n3', 'jlpt=2', 'grade<=4', 'top500', 'freq<=500', 'strokes=5-10', 'strokes=7'.
+    pub fn parse(s: &str) -> Result<KanjiFilter, String> {
+        let mut filter = KanjiFilter::default();
+        for term in s.split_whitespace() {
+            let lower = term.to_lowercase();
+            if let Some(n) = lower.strip_prefix('n') {
+                let level = parse_number(term, n)?;
+                filter.jlpt = Some(
+                    old_jlpt_level(level)
+                        .ok_or_else(|| format!("there is no JLPT level N{}", level))?,
+                );
+            } else if let Some(level) = lower.strip_prefix("jlpt=") {
+                filter.jlpt = Some(parse_number(term, level)?);
+            } else if let Some(grade) = lower.strip_prefix("grade<=") {
+                filter.max_grade = Some(parse_number(term, grade)?);
+            } else if let Some(freq) = lower
+                .strip_prefix("top")
+                .or_else(|| lower.strip_prefix("freq<="))
+            {
+                filter.max_freq = Some(parse_number(term, freq)?);
+            } else if let Some(strokes) = lower.strip_prefix("strokes=") {
+                let (min, max) = strokes.split_once('-').unwrap_or((strokes, strokes));
+                filter.min_strokes = Some(parse_number(term, min)?);
+                filter.max_strokes = Some(parse_number(term, max)?);
+            } else {
+                return Err(format!("dont know what '{}' means", term));
+            }
+        }
+
+        Ok(filter)
+    }
+
+    pub fn matches(&self, kanji: &Kanji) -> bool {
+        let at_most = |limit: Option<u32>, value: Option<u32>| {
+            limit.is_none_or(|limit| value.is_some_and(|v| v <= limit))
+        };
+        let at_least = |limit: Option<u32>, value: Option<u32>| {
+            limit.is_none_or(|limit| value.is_some_and(|v| v >= limit))
+        };
+
+        self.jlpt.is_none_or(|jlpt| kanji.jlpt == Some(jlpt))
+            && at_most(self.max_grade, kanji.grade)
+            && at_most(self.max_freq, kanji.freq)
+            && at_least(self.min_strokes, kanji.stroke_count)
+            && at_most(self.max_strokes, kanji.stroke_count)
+    }
+}
+
+#[cfg(test)]
+mod filter_test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            KanjiFilter::parse("n3 grade<=4").unwrap(),
+            KanjiFilter {
+                jlpt: Some(2),
+                max_grade: Some(4),
+                ..KanjiFilter::default()
+            }
+        );
+        assert_eq!(
+            KanjiFilter::parse("top500 strokes=5-10").unwrap(),
+            KanjiFilter {
+                max_freq: Some(500),
+                min_strokes: Some(5),
+                max_strokes: Some(10),
+                ..KanjiFilter::default()
+            }
+        );
+        assert_eq!(
+            KanjiFilter::parse("jlpt=1 strokes=7 freq<=100").unwrap(),
+            KanjiFilter {
+                jlpt: Some(1),
+                max_freq: Some(100),
+                min_strokes: Some(7),
+                max_strokes: Some(7),
+                ..KanjiFilter::default()
+            }
+        );
+        assert_eq!(KanjiFilter::parse("").unwrap(), KanjiFilter::default());
+        assert!(KanjiFilter::parse("n6").is_err());
+        assert!(KanjiFilter::parse("grade<=x").is_err());
+        assert!(KanjiFilter::parse("radical").is_err());
+    }
+}
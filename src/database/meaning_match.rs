@@ -0,0 +1,140 @@
+// how well a query matched a meaning, better matches compare as smaller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchQuality {
+    Exact,
+    // query is the start of the meaning: 'rank' for 'rank next'.
+    Prefix,
+    // whole words match: 'next' for 'rank next', or 'come after' inside a longer query.
+    Words,
+    // one contains the other: 'asian' for 'Asia'.
+    Substring,
+    // every word matched a word of the meaning with a typo or two: 'aisa' for 'Asia'.
+    Fuzzy,
+}
+
+// too short words would match almost anything once typos or substrings are allowed.
+const MIN_SUBSTRING_LEN: usize = 4;
+
+fn words(s: &str) -> Vec<&str> {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn contains_sequence(haystack: &[&str], needle: &[&str]) -> bool {
+    !needle.is_empty() && haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+// optimal string alignment distance, swapped neighbouring letters count as a single typo.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+fn allowed_typos(word: &str) -> usize {
+    match word.chars().count() {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
+
+// case insensitive, None if the meaning has nothing to do with the query.
+pub fn match_meaning(query: &str, meaning: &str) -> Option<MatchQuality> {
+    let query = query.trim().to_lowercase();
+    let meaning = meaning.to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+
+    if query == meaning {
+        return Some(MatchQuality::Exact);
+    }
+
+    let query_words = words(&query);
+    let meaning_words = words(&meaning);
+    if query_words.is_empty() {
+        return None;
+    }
+
+    if meaning_words.starts_with(&query_words) {
+        return Some(MatchQuality::Prefix);
+    }
+
+    if contains_sequence(&meaning_words, &query_words)
+        || contains_sequence(&query_words, &meaning_words)
+    {
+        return Some(MatchQuality::Words);
+    }
+
+    let long_enough = |s: &str| s.chars().count() >= MIN_SUBSTRING_LEN;
+    if (long_enough(&query) && meaning.contains(&query))
+        || (long_enough(&meaning) && query.contains(&meaning))
+    {
+        return Some(MatchQuality::Substring);
+    }
+
+    let typo_match = |q: &str| {
+        let allowed = allowed_typos(q);
+        allowed > 0 && meaning_words.iter().any(|m| edit_distance(q, m) <= allowed)
+    };
+    if query_words.iter().all(|q| typo_match(q)) {
+        return Some(MatchQuality::Fuzzy);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod meaning_match_test {
+    use super::*;
+
+    #[test]
+    fn qualities() {
+        assert_eq!(match_meaning("Asia", "Asia"), Some(MatchQuality::Exact));
+        assert_eq!(match_meaning("asia", "Asia"), Some(MatchQuality::Exact));
+        assert_eq!(
+            match_meaning("rank", "rank next"),
+            Some(MatchQuality::Prefix)
+        );
+        assert_eq!(
+            match_meaning("next", "rank next"),
+            Some(MatchQuality::Words)
+        );
+        assert_eq!(
+            match_meaning("what does come after mean", "come after"),
+            Some(MatchQuality::Words)
+        );
+        assert_eq!(
+            match_meaning("asian", "Asia"),
+            Some(MatchQuality::Substring)
+        );
+        assert_eq!(match_meaning("aisa", "Asia"), Some(MatchQuality::Fuzzy));
+        assert_eq!(
+            match_meaning("cmoe aftr", "come after"),
+            Some(MatchQuality::Fuzzy)
+        );
+        assert_eq!(match_meaning("ous", "-ous"), Some(MatchQuality::Prefix));
+        assert_eq!(match_meaning("dog", "dumb"), None);
+        assert_eq!(match_meaning("mute", "Asia"), None);
+    }
+}
@@ -0,0 +1,883 @@
+mod components;
+mod filter;
+mod meaning_match;
+mod query_code;
+mod radicals;
+
+pub use components::*;
+pub use filter::*;
+pub use meaning_match::*;
+pub use query_code::*;
+pub use radicals::*;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{BufReader, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::kana;
+use crate::kanji_dict;
+use crate::kanji_strokes::{self, KanjiDrawRecipe};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Kanji {
+    pub on_readings: Vec<String>,
+    pub kun_readings: Vec<String>,
+    // language code -> meanings, KANJIDIC2 has en for almost all of them, fr, es and pt for some.
+    pub meanings: BTreeMap<String, Vec<String>>,
+    pub literal: char,
+    // rank among the 2500 most used kanjis in newspapers, 1 is the most frequent one.
+    pub freq: Option<u32>,
+    pub grade: Option<u32>,
+    pub jlpt: Option<u32>,
+    pub stroke_count: Option<u32>,
+    // name readings.
+    pub nanori: Vec<String>,
+    // dr_type -> index of the kanji in that dictionary or textbook.
+    pub dic_refs: BTreeMap<String, String>,
+    // including the commonly mistaken ones, so a wrong guess still finds the kanji.
+    pub skip: Vec<String>,
+    pub four_corner: Vec<String>,
+    // readings in other languages: pinyin with the tone number 'ya4', korean romanized and hangul, vietnamese.
+    pub pinyin: Vec<String>,
+    pub korean_r: Vec<String>,
+    pub korean_h: Vec<String>,
+    pub vietnam: Vec<String>,
+    // number of the Kangxi radical the kanji is filed under.
+    pub radical: Option<u32>,
+    // Nelson files some kanjis under a different radical than Kangxi does.
+    pub nelson_radical: Option<u32>,
+}
+
+impl Kanji {
+    pub fn pretty_print(&self, languages: &[String], foreign_readings: bool) -> String {
+        // maybe as display one day.
+        let mut printed = format!(
+            "kanji: {}, meanings: {:?}, on_readings: {:?}, kun_readings: {:?}",
+            self.literal,
+            self.meaning(languages),
+            self.on_readings,
+            self.kun_readings
+        );
+        if !self.nanori.is_empty() {
+            printed += &format!(", nanori: {:?}", self.nanori);
+        }
+        if foreign_readings {
+            let foreign = [
+                ("pinyin", &self.pinyin),
+                ("korean", &self.korean_h),
+                ("korean_r", &self.korean_r),
+                ("vietnam", &self.vietnam),
+            ];
+            for (name, readings) in foreign.iter() {
+                if !readings.is_empty() {
+                    printed += &format!(", {}: {:?}", name, readings);
+                }
+            }
+        }
+
+        if let Some(radical) = self.radical.and_then(radical) {
+            printed += &format!(", radical: {} {}", radical.number, radical.glyph);
+        }
+        if let Some(skip) = self.skip.first() {
+            printed += &format!(", skip: {}", skip);
+        }
+
+        let misc = [
+            ("strokes", self.stroke_count),
+            ("grade", self.grade),
+            ("jlpt", self.jlpt),
+            ("freq", self.freq),
+        ];
+        for (name, value) in misc.iter() {
+            if let Some(value) = value {
+                printed += &format!(", {}: {}", name, value);
+            }
+        }
+
+        printed
+    }
+
+    // meanings in the first of the preferred languages the kanji has them in, English otherwise.
+    pub fn meaning(&self, languages: &[String]) -> &[String] {
+        languages
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(ENGLISH))
+            .find_map(|lang| self.meanings.get(lang).filter(|m| !m.is_empty()))
+            .map_or(&[], |m| m.as_slice())
+    }
+
+    // meanings in all the preferred languages and English, any of them can be searched by.
+    fn searchable_meanings<'a>(
+        &'a self,
+        languages: &'a [String],
+    ) -> impl Iterator<Item = &'a String> + 'a {
+        self.meanings
+            .iter()
+            .filter(move |(lang, _)| *lang == ENGLISH || languages.contains(lang))
+            .flat_map(|(_, meanings)| meanings.iter())
+    }
+
+    // reading is expected in hiragana, normalized the way the options say.
+    fn has_reading(&self, reading: &str, options: &SearchOptions) -> bool {
+        let nanori: &[String] = if options.include_nanori {
+            &self.nanori
+        } else {
+            &[]
+        };
+        self.on_readings
+            .iter()
+            .chain(self.kun_readings.iter())
+            .chain(nanori.iter())
+            .map(|r| options.normalize_reading(r))
+            .any(|r| match options.reading_match {
+                ReadingMatch::Exact => r == reading,
+                ReadingMatch::Prefix => r.starts_with(reading),
+            })
+    }
+
+    // pinyin with a tone number has to match exactly, without one any tone does.
+    // the rest only when the options allow it, short romanized readings match too many kanjis.
+    fn has_foreign_reading(&self, pattern: &str, options: &SearchOptions) -> bool {
+        let pattern = pattern.trim().to_lowercase();
+        if pattern.is_empty() {
+            return false;
+        }
+
+        let has_tone = pattern.ends_with(|c: char| c.is_ascii_digit());
+        if has_tone {
+            return self.pinyin.contains(&pattern);
+        }
+        if !options.foreign_readings {
+            return false;
+        }
+
+        let without_tone =
+            |r: &String| r.trim_end_matches(|c: char| c.is_ascii_digit()).to_string();
+        self.pinyin.iter().map(without_tone).any(|r| r == pattern)
+            || self
+                .korean_r
+                .iter()
+                .chain(self.korean_h.iter())
+                .chain(self.vietnam.iter())
+                .any(|r| r.to_lowercase() == pattern)
+    }
+
+    // typed answer has to be one of on or kun readings, kun ones may drop their okurigana.
+    pub fn matches_reading(&self, answer: &str) -> bool {
+        answer_matches(
+            answer,
+            self.on_readings.iter().chain(self.kun_readings.iter()),
+        )
+    }
+
+    pub fn matches_nanori(&self, answer: &str) -> bool {
+        answer_matches(answer, self.nanori.iter())
+    }
+}
+
+pub const ENGLISH: &str = "en";
+
+// compared in hiragana, so on readings match whether they were typed in lower or upper case romaji.
+fn answer_matches<'a>(answer: &str, mut readings: impl Iterator<Item = &'a String>) -> bool {
+    let answer = kana::romaji_to_reading(answer).unwrap_or_else(|| answer.trim().to_string());
+    let answer = kana::katakana_to_hiragana(&answer);
+    !answer.is_empty() && readings.any(|r| kana::reading_variants(r).contains(&answer))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadingMatch {
+    Exact,
+    Prefix,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SearchOptions {
+    pub reading_match: ReadingMatch,
+    // KANJIDIC2 separates okurigana with a dot: 'つ.ぐ', ignoring it lets 'つぐ' match as well.
+    pub ignore_okurigana_dot: bool,
+    // look up name readings along with on and kun ones, off so names dont crowd normal searches.
+    pub include_nanori: bool,
+    // look up pinyin without tone, korean and vietnamese readings too, pinyin with tone always is.
+    pub foreign_readings: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            reading_match: ReadingMatch::Exact,
+            ignore_okurigana_dot: true,
+            include_nanori: false,
+            foreign_readings: false,
+        }
+    }
+}
+
+impl SearchOptions {
+    // on and kun readings compare equal in hiragana, affix dashes never matter.
+    fn normalize_reading(&self, reading: &str) -> String {
+        let reading = kana::katakana_to_hiragana(reading.trim_matches('-'));
+        if self.ignore_okurigana_dot {
+            reading.replace('.', "")
+        } else {
+            reading
+        }
+    }
+}
+
+pub fn convert_parsed_to_kanji_vec(kanji_dictionary: &kanji_dict::KanjiDictionary) -> Vec<Kanji> {
+    let mut kanji_vec = Vec::new();
+    for c in &kanji_dictionary.character {
+        let mut reading_on = Vec::new();
+        let mut reading_kun = Vec::new();
+        let mut meanings: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut nanori = Vec::new();
+        let mut foreign: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        if let Some(reading_meaning) = c.reading_meaning.clone() {
+            nanori = reading_meaning.nanori.unwrap_or_default();
+
+            let reading = reading_meaning.rmgroup.reading.unwrap_or_default();
+            for r in reading {
+                if r.r_type == "ja_on" {
+                    reading_on.push(r.value)
+                } else if r.r_type == "ja_kun" {
+                    reading_kun.push(r.value)
+                } else {
+                    foreign.entry(r.r_type).or_default().push(r.value)
+                }
+            }
+
+            let meaning_dict = reading_meaning.rmgroup.meaning.unwrap_or_default();
+            for m in meaning_dict {
+                meanings.entry(m.m_lang).or_default().push(m.value);
+            }
+        }
+
+        let rad_value = |rad_type: &str| {
+            c.radical
+                .rad_value
+                .iter()
+                .find(|r| r.rad_type == rad_type)
+                .and_then(|r| r.value.trim().parse().ok())
+        };
+        let q_codes = c.query_code.iter().flat_map(|q| q.q_code.iter());
+        let codes = |qc_type: &str| {
+            q_codes
+                .clone()
+                .filter(|q| q.qc_type == qc_type)
+                .map(|q| q.value.clone())
+                .collect()
+        };
+
+        kanji_vec.push(Kanji {
+            meanings,
+            literal: c.literal,
+            kun_readings: reading_kun,
+            on_readings: reading_on,
+            freq: c.misc.freq,
+            grade: c.misc.grade,
+            jlpt: c.misc.jlpt,
+            // the first one is the accepted count, rest are common miscounts.
+            stroke_count: c.misc.stroke_count.first().copied(),
+            nanori,
+            dic_refs: c
+                .dic_number
+                .iter()
+                .flat_map(|d| d.dic_ref.iter())
+                .map(|r| (r.dr_type.clone(), r.value.clone()))
+                .collect(),
+            skip: codes("skip"),
+            four_corner: codes("four_corner"),
+            pinyin: foreign.remove("pinyin").unwrap_or_default(),
+            korean_r: foreign.remove("korean_r").unwrap_or_default(),
+            korean_h: foreign.remove("korean_h").unwrap_or_default(),
+            vietnam: foreign.remove("vietnam").unwrap_or_default(),
+            radical: rad_value("classical"),
+            nelson_radical: rad_value("nelson_c"),
+        });
+    }
+
+    kanji_vec
+}
+
+// bump whenever Database changes shape, db.bin written by an older version gets rebuilt.
+const DB_VERSION: u32 = 13;
+
+pub fn dump_db(db: &Database) {
+    let mut f = std::fs::OpenOptions::new()
+        .truncate(true)
+        .write(true)
+        .create(true)
+        .open("db.bin")
+        .expect("Couldnt open file db.bin for writing.");
+    bincode::serialize_into(&mut f, &DB_VERSION).expect("Couldnt serialize db.");
+    bincode::serialize_into(&mut f, db).expect("Couldnt serialize db.");
+    f.flush().expect("Couldnt write db.bin");
+}
+
+// None if there is no db.bin yet or it was written by a different version.
+// also None when KRADFILE showed up after db.bin was built from KanjiVG components.
+pub fn load_db_from_plain_file() -> Option<Database> {
+    let f = std::fs::OpenOptions::new().read(true).open("db.bin").ok()?;
+    let mut reader = BufReader::new(f);
+    let version: u32 = bincode::deserialize_from(&mut reader).ok()?;
+    if version != DB_VERSION {
+        return None;
+    }
+    let mut db: Database = bincode::deserialize_from(reader).ok()?;
+    if !db.components_from_kradfile && std::path::Path::new(KRADFILE).exists() {
+        return None;
+    }
+    db.index_kanjis();
+    Some(db)
+}
+
+pub fn build_db() -> Database {
+    let kanjivg = kanji_strokes::parse_kanjivg();
+    let strokes = kanji_strokes::kanjivg_into_strokes(&kanjivg);
+    let kradfile = load_kradfile();
+    let components_from_kradfile = kradfile.is_some();
+    let components = kradfile.unwrap_or_else(|| kanji_strokes::kanjivg_into_components(&kanjivg));
+    let decompositions = kanji_strokes::kanjivg_into_decompositions(&kanjivg);
+    let dict = parse_dict();
+
+    let mut db = Database {
+        strokes: strokes.dict,
+        kanjis: convert_parsed_to_kanji_vec(&dict),
+        components,
+        components_from_kradfile,
+        decompositions,
+        by_literal: HashMap::new(),
+    };
+    db.index_kanjis();
+    db
+}
+
+pub fn parse_dict() -> kanji_dict::KanjiDictionary {
+    serde_xml_rs::from_reader(std::fs::File::open("kanjidic2.xml").expect("Couldnt open dict file"))
+        .expect("Couldnt load dict!")
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Database {
+    pub kanjis: Vec<Kanji>,
+    pub strokes: BTreeMap<char, kanji_strokes::KanjiDrawRecipe>,
+    // kanji -> components it is made of, from KRADFILE or KanjiVG.
+    pub components: BTreeMap<char, BTreeSet<char>>,
+    pub components_from_kradfile: bool,
+    // kanji -> how KanjiVG draws it, part by part.
+    pub decompositions: BTreeMap<char, kanji_strokes::Component>,
+    // literal -> position in kanjis, rebuilt by index_kanjis instead of stored in db.bin.
+    #[serde(skip)]
+    pub by_literal: HashMap<char, usize>,
+}
+
+impl Database {
+    // has to run again whenever kanjis change.
+    pub fn index_kanjis(&mut self) {
+        self.by_literal = self
+            .kanjis
+            .iter()
+            .enumerate()
+            .map(|(i, k)| (k.literal, i))
+            .collect();
+    }
+
+    pub fn kanji(&self, literal: char) -> Option<&Kanji> {
+        self.by_literal.get(&literal).map(|i| &self.kanjis[*i])
+    }
+
+    pub fn get(&self, literal: char) -> Option<(Kanji, Option<KanjiDrawRecipe>)> {
+        self.kanji(literal)
+            .map(|k| (k.clone(), self.strokes.get(&k.literal).cloned()))
+    }
+
+    // every kanji the filter lets through, most frequent first.
+    pub fn filter(&self, filter: &KanjiFilter) -> Vec<char> {
+        let mut matching: Vec<&Kanji> = self.kanjis.iter().filter(|k| filter.matches(k)).collect();
+        matching.sort_by_key(|k| k.freq.unwrap_or(u32::MAX));
+        matching.into_iter().map(|k| k.literal).collect()
+    }
+
+    // kanjis filed under the Kangxi radical, simplest ones first.
+    pub fn by_radical(&self, number: u32) -> Vec<char> {
+        let mut matching: Vec<&Kanji> = self
+            .kanjis
+            .iter()
+            .filter(|k| k.radical == Some(number))
+            .collect();
+        matching.sort_by_key(|k| (k.stroke_count, k.freq.unwrap_or(u32::MAX)));
+        matching.into_iter().map(|k| k.literal).collect()
+    }
+
+    // kanjis made of all the given components, simplest ones first.
+    pub fn find_by_components(&self, components: &BTreeSet<char>) -> Vec<char> {
+        let mut matching: Vec<(Option<u32>, u32, char)> = self
+            .components
+            .iter()
+            .filter(|(_, has)| components.is_subset(has))
+            .map(|(literal, _)| {
+                let k = self.kanji(*literal);
+                (
+                    k.and_then(|k| k.stroke_count),
+                    k.and_then(|k| k.freq).unwrap_or(u32::MAX),
+                    *literal,
+                )
+            })
+            .collect();
+        matching.sort();
+        matching
+            .into_iter()
+            .map(|(_, _, literal)| literal)
+            .collect()
+    }
+
+    // components that can still be added to the picked ones and find something.
+    pub fn possible_components(&self, candidates: &[char]) -> BTreeSet<char> {
+        candidates
+            .iter()
+            .filter_map(|k| self.components.get(k))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    pub fn dic_ref_types(&self) -> BTreeSet<String> {
+        self.kanjis
+            .iter()
+            .flat_map(|k| k.dic_refs.keys().cloned())
+            .collect()
+    }
+
+    // kanjis in the order the given dictionary or textbook teaches them.
+    pub fn textbook_order(&self, dr_type: &str) -> Vec<char> {
+        let mut indexed: Vec<(Vec<u32>, char)> = self
+            .kanjis
+            .iter()
+            .filter_map(|k| {
+                let index = k.dic_refs.get(dr_type)?;
+                // some are chapter.position like '3.11', unparsable ones go last.
+                let key = index
+                    .split('.')
+                    .map(|n| n.trim().parse().unwrap_or(u32::MAX))
+                    .collect();
+                Some((key, k.literal))
+            })
+            .collect();
+        indexed.sort();
+        indexed.into_iter().map(|(_, literal)| literal).collect()
+    }
+
+    pub fn matches_filter(&self, literal: char, filter: &KanjiFilter) -> bool {
+        self.kanji(literal).is_some_and(|k| filter.matches(k))
+    }
+
+    // results are ranked by how well they matched, equally good ones by frequency of use.
+    // pattern ending with '*' looks readings up by prefix no matter what the options say.
+    // SKIP ('1-3-*') and four corner ('1010') codes are looked up as such.
+    // meanings are searched in the given languages and English.
+    pub fn find(
+        &self,
+        pattern: &str,
+        options: &SearchOptions,
+        languages: &[String],
+    ) -> Vec<(Kanji, Option<KanjiDrawRecipe>)> {
+        let by_code = if let Some(skip) = SkipPattern::parse(pattern) {
+            Some(self.find_by_skip(&skip))
+        } else {
+            FourCornerPattern::parse(pattern).map(|corner| self.find_by_four_corner(&corner))
+        };
+        if let Some(found) = by_code {
+            return found
+                .into_iter()
+                .filter_map(|literal| self.get(literal))
+                .collect();
+        }
+
+        let mut matching_kanjis = Vec::new();
+        let reading = match pattern.strip_suffix('*') {
+            Some(prefix) => self.find_by_reading(
+                prefix,
+                &SearchOptions {
+                    reading_match: ReadingMatch::Prefix,
+                    ..options.clone()
+                },
+            ),
+            None => self.find_by_reading(pattern, options),
+        };
+
+        for k in &self.kanjis {
+            let quality = if pattern.contains(k.literal)
+                || reading.contains(&k.literal)
+                || k.has_foreign_reading(pattern, options)
+            {
+                Some(MatchQuality::Exact)
+            } else {
+                k.searchable_meanings(languages)
+                    .filter_map(|m| match_meaning(pattern, m))
+                    .min()
+            };
+
+            if let Some(quality) = quality {
+                matching_kanjis.push((quality, k));
+            }
+        }
+
+        // kanjis without frequency are the rare ones, they go last.
+        matching_kanjis.sort_by_key(|(quality, k)| (*quality, k.freq.unwrap_or(u32::MAX)));
+        matching_kanjis
+            .into_iter()
+            .map(|(_, k)| (k.clone(), self.strokes.get(&k.literal).cloned()))
+            .collect()
+    }
+
+    pub fn find_by_skip(&self, pattern: &SkipPattern) -> Vec<char> {
+        self.find_by_code(|k| k.skip.iter().any(|c| pattern.matches(c)))
+    }
+
+    pub fn find_by_four_corner(&self, pattern: &FourCornerPattern) -> Vec<char> {
+        self.find_by_code(|k| k.four_corner.iter().any(|c| pattern.matches(c)))
+    }
+
+    // most frequent first, codes match way too many kanjis to rank them any other way.
+    fn find_by_code(&self, matches: impl Fn(&Kanji) -> bool) -> Vec<char> {
+        let mut matching: Vec<&Kanji> = self.kanjis.iter().filter(|k| matches(k)).collect();
+        matching.sort_by_key(|k| k.freq.unwrap_or(u32::MAX));
+        matching.into_iter().map(|k| k.literal).collect()
+    }
+
+    // reading in romaji, hiragana or katakana: 'a', 'あ' and 'ア' all look up the same kanjis.
+    pub fn find_by_reading(&self, reading: &str, options: &SearchOptions) -> Vec<char> {
+        let reading = match kana::romaji_to_reading(reading) {
+            Some(reading) => options.normalize_reading(&reading),
+            None => return Vec::new(),
+        };
+
+        self.kanjis
+            .iter()
+            .filter(|k| k.has_reading(&reading, options))
+            .map(|k| k.literal)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod database_test {
+    use super::*;
+
+    fn kanji(literal: char, on: &[&str], kun: &[&str], meaning: &[&str]) -> Kanji {
+        let strings = |s: &[&str]| s.iter().map(|s| s.to_string()).collect();
+        Kanji {
+            on_readings: strings(on),
+            kun_readings: strings(kun),
+            meanings: std::iter::once((ENGLISH.to_string(), strings(meaning))).collect(),
+            literal,
+            freq: None,
+            grade: None,
+            jlpt: None,
+            stroke_count: None,
+            nanori: Vec::new(),
+            dic_refs: BTreeMap::new(),
+            skip: Vec::new(),
+            four_corner: Vec::new(),
+            pinyin: Vec::new(),
+            korean_r: Vec::new(),
+            korean_h: Vec::new(),
+            vietnam: Vec::new(),
+            radical: None,
+            nelson_radical: None,
+        }
+    }
+
+    fn test_db() -> Database {
+        let mut db = Database {
+            kanjis: vec![
+                kanji(
+                    '亜',
+                    &["ア"],
+                    &["つ.ぐ"],
+                    &["Asia", "rank next", "come after"],
+                ),
+                kanji('唖', &["ア", "アク"], &["おし"], &["mute", "dumb"]),
+                kanji('上', &["ジョウ"], &["うえ", "-うえ", "あ.がる"], &["above"]),
+            ],
+            strokes: BTreeMap::new(),
+            components: BTreeMap::new(),
+            components_from_kradfile: false,
+            decompositions: BTreeMap::new(),
+            by_literal: HashMap::new(),
+        };
+        db.index_kanjis();
+        db
+    }
+
+    #[test]
+    fn reading_lookup() {
+        let db = test_db();
+        let exact = SearchOptions::default();
+        assert_eq!(db.find_by_reading("a", &exact), vec!['亜', '唖']);
+        assert_eq!(db.find_by_reading("あ", &exact), vec!['亜', '唖']);
+        assert_eq!(db.find_by_reading("ア", &exact), vec!['亜', '唖']);
+        assert_eq!(db.find_by_reading("tsugu", &exact), vec!['亜']);
+        assert_eq!(db.find_by_reading("tsu.gu", &exact), vec!['亜']);
+        assert_eq!(db.find_by_reading("ue", &exact), vec!['上']);
+
+        let strict = SearchOptions {
+            ignore_okurigana_dot: false,
+            ..SearchOptions::default()
+        };
+        assert!(db.find_by_reading("tsugu", &strict).is_empty());
+        assert_eq!(db.find_by_reading("tsu.gu", &strict), vec!['亜']);
+
+        let prefix = SearchOptions {
+            reading_match: ReadingMatch::Prefix,
+            ..SearchOptions::default()
+        };
+        assert_eq!(db.find_by_reading("a", &prefix), vec!['亜', '唖', '上']);
+
+        let found: Vec<char> = db
+            .find("aku*", &exact, &[])
+            .iter()
+            .map(|r| r.0.literal)
+            .collect();
+        assert_eq!(found, vec!['唖']);
+        let found: Vec<char> = db
+            .find("a", &exact, &[])
+            .iter()
+            .map(|r| r.0.literal)
+            .collect();
+        assert_eq!(found, vec!['亜', '唖']);
+    }
+
+    #[test]
+    fn nanori_lookup() {
+        let mut db = test_db();
+        db.kanjis[0].nanori = vec!["や".to_string(), "つぎ".to_string()];
+
+        let options = SearchOptions::default();
+        assert!(db.find_by_reading("tsugi", &options).is_empty());
+        assert_eq!(db.find_by_reading("a", &options), vec!['亜', '唖']);
+        let with_nanori = SearchOptions {
+            include_nanori: true,
+            ..SearchOptions::default()
+        };
+        assert_eq!(db.find_by_reading("tsugi", &with_nanori), vec!['亜']);
+
+        assert!(db.kanjis[0].matches_nanori("や"));
+        assert!(!db.kanjis[0].matches_nanori("あ"));
+        assert!(db.kanjis[0].matches_reading("あ"));
+    }
+
+    #[test]
+    fn on_reading_answers() {
+        let db = test_db();
+        let mute = &db.kanjis[1];
+        // アク is an on reading, lower case romaji turns into hiragana and still matches it.
+        assert!(mute.matches_reading(&kana::romaji_to_kana("aku", true)));
+        assert!(mute.matches_reading(&kana::romaji_to_kana("AKU", true)));
+        assert!(mute.matches_reading("aku"));
+        assert!(mute.matches_reading("アク"));
+        assert!(!mute.matches_reading("aki"));
+    }
+
+    #[test]
+    fn textbook_order() {
+        let mut db = test_db();
+        let refs = |index: &str| {
+            let mut refs = BTreeMap::new();
+            refs.insert("busy_people".to_string(), index.to_string());
+            refs
+        };
+        db.kanjis[0].dic_refs = refs("3.11");
+        db.kanjis[2].dic_refs = refs("3.2");
+
+        assert_eq!(db.textbook_order("busy_people"), vec!['上', '亜']);
+        assert!(db.textbook_order("heisig6").is_empty());
+        assert_eq!(db.dic_ref_types().len(), 1);
+    }
+
+    #[test]
+    fn code_lookup() {
+        let mut db = test_db();
+        db.kanjis[0].skip = vec!["4-7-1".to_string()];
+        db.kanjis[0].four_corner = vec!["1010.6".to_string()];
+        db.kanjis[1].skip = vec!["1-3-7".to_string()];
+        db.kanjis[2].skip = vec!["4-3-2".to_string()];
+        db.kanjis[2].freq = Some(35);
+
+        let options = SearchOptions::default();
+        let found = |pattern: &str| -> Vec<char> {
+            db.find(pattern, &options, &[])
+                .into_iter()
+                .map(|(k, _)| k.literal)
+                .collect()
+        };
+        assert_eq!(found("4-*-*"), vec!['上', '亜']);
+        assert_eq!(found("1-3-7"), vec!['唖']);
+        assert_eq!(found("1010"), vec!['亜']);
+        assert!(found("2-*-*").is_empty());
+    }
+
+    #[test]
+    fn ranked_meaning_search() {
+        let mut db = test_db();
+        db.kanjis.push(Kanji {
+            freq: Some(900),
+            ..kanji('次', &["ジ"], &["つ.ぐ", "つぎ"], &["next", "order"])
+        });
+        db.kanjis[0].freq = Some(1509);
+        db.index_kanjis();
+        let options = SearchOptions::default();
+        let found = |pattern| -> Vec<char> {
+            db.find(pattern, &options, &[])
+                .iter()
+                .map(|r| r.0.literal)
+                .collect()
+        };
+
+        assert_eq!(found("asia"), vec!['亜']);
+        assert_eq!(found("asian"), vec!['亜']);
+        assert_eq!(found("to come after"), vec!['亜']);
+        // exact match first, then by frequency.
+        assert_eq!(found("next"), vec!['次', '亜']);
+        assert_eq!(found("rank next"), vec!['亜', '次']);
+        assert_eq!(found("dumm"), vec!['唖']);
+    }
+
+    #[test]
+    fn preferred_languages() {
+        let mut db = test_db();
+        db.kanjis[1]
+            .meanings
+            .insert("es".to_string(), vec!["mudo".to_string()]);
+        let spanish = vec!["es".to_string()];
+        let french = vec!["fr".to_string()];
+
+        assert_eq!(db.kanjis[1].meaning(&spanish), &["mudo".to_string()]);
+        assert_eq!(db.kanjis[1].meaning(&french), &["mute", "dumb"]);
+        assert_eq!(
+            db.kanjis[0].meaning(&spanish),
+            &["Asia", "rank next", "come after"]
+        );
+
+        let options = SearchOptions::default();
+        let found = |pattern, languages: &[String]| -> Vec<char> {
+            db.find(pattern, &options, languages)
+                .iter()
+                .map(|r| r.0.literal)
+                .collect()
+        };
+        assert_eq!(found("mudo", &spanish), vec!['唖']);
+        assert_eq!(found("mute", &spanish), vec!['唖']);
+        assert!(found("mudo", &french).is_empty());
+    }
+
+    #[test]
+    fn foreign_reading_lookup() {
+        let mut db = test_db();
+        db.kanjis[0].pinyin = vec!["ya4".to_string()];
+        db.kanjis[0].korean_h = vec!["아".to_string()];
+        db.kanjis[1].pinyin = vec!["ya1".to_string()];
+
+        let found = |pattern, options: &SearchOptions| -> Vec<char> {
+            db.find(pattern, options, &[])
+                .iter()
+                .map(|r| r.0.literal)
+                .collect()
+        };
+        let japanese_only = SearchOptions::default();
+        assert_eq!(found("ya4", &japanese_only), vec!['亜']);
+        assert!(found("아", &japanese_only).is_empty());
+
+        let foreign = SearchOptions {
+            foreign_readings: true,
+            ..SearchOptions::default()
+        };
+        assert_eq!(found("ya1", &foreign), vec!['唖']);
+        assert_eq!(found("ya", &foreign).len(), 2);
+        assert_eq!(found("아", &foreign), vec!['亜']);
+        assert!(db.kanjis[0]
+            .pretty_print(&[], true)
+            .contains("pinyin: [\"ya4\"]"));
+        assert!(!db.kanjis[0].pretty_print(&[], false).contains("pinyin"));
+    }
+
+    #[test]
+    fn radical_lookup() {
+        let mut db = test_db();
+        db.kanjis[0].radical = Some(7);
+        db.kanjis[0].stroke_count = Some(7);
+        db.kanjis[1].radical = Some(30);
+        db.kanjis[2].radical = Some(7);
+        db.kanjis[2].stroke_count = Some(3);
+
+        assert_eq!(db.by_radical(7), vec!['上', '亜']);
+        assert_eq!(db.by_radical(30), vec!['唖']);
+        assert!(db.by_radical(1).is_empty());
+        assert!(db.kanjis[0]
+            .pretty_print(&[], false)
+            .contains("radical: 7 二"));
+    }
+
+    #[test]
+    fn component_lookup() {
+        let mut db = test_db();
+        let components = |s: &str| s.chars().collect::<BTreeSet<char>>();
+        db.components.insert('亜', components("｜一口"));
+        db.components.insert('唖', components("｜一口"));
+        db.components.insert('上', components("｜一卜"));
+        db.kanjis[0].freq = Some(1509);
+
+        let found = db.find_by_components(&components("一口"));
+        assert_eq!(found, vec!['亜', '唖']);
+        assert_eq!(db.possible_components(&found), components("｜一口"));
+
+        let found = db.find_by_components(&components("一"));
+        assert_eq!(found.len(), 3);
+        assert_eq!(db.possible_components(&found), components("｜一口卜"));
+        assert!(db.find_by_components(&components("口卜")).is_empty());
+    }
+
+    #[test]
+    fn filters() {
+        let ah = Kanji {
+            grade: Some(8),
+            jlpt: Some(1),
+            freq: Some(1509),
+            stroke_count: Some(7),
+            ..kanji('亜', &["ア"], &["つ.ぐ"], &["Asia"])
+        };
+        let mute = Kanji {
+            stroke_count: Some(10),
+            ..kanji('唖', &["ア"], &["おし"], &["mute"])
+        };
+
+        assert!(KanjiFilter::default().matches(&mute));
+        let n1 = KanjiFilter {
+            jlpt: Some(1),
+            ..KanjiFilter::default()
+        };
+        assert!(n1.matches(&ah));
+        assert!(!n1.matches(&mute));
+
+        let short = KanjiFilter {
+            min_strokes: Some(5),
+            max_strokes: Some(8),
+            ..KanjiFilter::default()
+        };
+        assert!(short.matches(&ah));
+        assert!(!short.matches(&mute));
+
+        let common = KanjiFilter {
+            max_freq: Some(500),
+            ..KanjiFilter::default()
+        };
+        assert!(!common.matches(&ah));
+        assert!(!common.matches(&mute));
+        assert!(ah
+            .pretty_print(&[], false)
+            .ends_with("strokes: 7, grade: 8, jlpt: 1, freq: 1509"));
+    }
+}
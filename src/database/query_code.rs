@@ -0,0 +1,92 @@
+// lookup codes describing how a kanji looks, for the ones we can see but cannot read.
+
+// SKIP 'shape-strokes-strokes' where any part can be '*': '1-3-*', '4-*-1'.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkipPattern([Option<u32>; 3]);
+
+impl SkipPattern {
+    pub fn parse(pattern: &str) -> Option<SkipPattern> {
+        let mut parts = [None; 3];
+        let mut count = 0;
+        for part in pattern.trim().split('-') {
+            if count == parts.len() {
+                return None;
+            }
+            if part != "*" {
+                parts[count] = Some(part.parse().ok()?);
+            }
+            count += 1;
+        }
+
+        if count == parts.len() {
+            Some(SkipPattern(parts))
+        } else {
+            None
+        }
+    }
+
+    pub fn matches(&self, code: &str) -> bool {
+        let code: Vec<&str> = code.split('-').collect();
+        code.len() == self.0.len()
+            && self
+                .0
+                .iter()
+                .zip(code)
+                .all(|(p, c)| p.is_none_or(|p| c.parse() == Ok(p)))
+    }
+}
+
+// four corner '1010.6', the '.6' extra corner can be left out and any digit can be '*'.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FourCornerPattern(Vec<char>);
+
+impl FourCornerPattern {
+    pub fn parse(pattern: &str) -> Option<FourCornerPattern> {
+        let pattern: Vec<char> = pattern.trim().chars().collect();
+        let digit = |c: &char| c.is_ascii_digit() || *c == '*';
+        let valid = match pattern.len() {
+            4 => pattern.iter().all(digit),
+            6 => pattern[..4].iter().all(digit) && pattern[4] == '.' && digit(&pattern[5]),
+            _ => false,
+        };
+
+        if valid {
+            Some(FourCornerPattern(pattern))
+        } else {
+            None
+        }
+    }
+
+    pub fn matches(&self, code: &str) -> bool {
+        let code: Vec<char> = code.chars().collect();
+        code.len() >= self.0.len() && self.0.iter().zip(code).all(|(p, c)| *p == '*' || *p == c)
+    }
+}
+
+#[cfg(test)]
+mod query_code_test {
+    use super::*;
+
+    #[test]
+    fn patterns() {
+        let skip = SkipPattern::parse("1-3-*").unwrap();
+        assert!(skip.matches("1-3-7"));
+        assert!(!skip.matches("1-4-7"));
+        assert!(SkipPattern::parse("4-*-1").unwrap().matches("4-7-1"));
+        assert!(SkipPattern::parse("1-3").is_none());
+        assert!(SkipPattern::parse("1-3-7-1").is_none());
+        assert!(SkipPattern::parse("a-3-7").is_none());
+
+        let corner = FourCornerPattern::parse("1010").unwrap();
+        assert!(corner.matches("1010.6"));
+        assert!(!corner.matches("1011.6"));
+        assert!(FourCornerPattern::parse("6*01.7")
+            .unwrap()
+            .matches("6101.7"));
+        assert!(!FourCornerPattern::parse("6101.2")
+            .unwrap()
+            .matches("6101.7"));
+        assert!(FourCornerPattern::parse("101").is_none());
+        assert!(FourCornerPattern::parse("top5").is_none());
+    }
+}
@@ -0,0 +1,284 @@
+// the 214 Kangxi radicals KANJIDIC2 classical radical numbers point into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Radical {
+    pub number: u32,
+    // traditional forms, the way Kangxi lists them.
+    pub glyph: char,
+    pub name: &'static str,
+    pub strokes: u32,
+}
+
+impl Radical {
+    pub fn pretty_print(&self) -> String {
+        format!(
+            "{} {} {} ({} strokes)",
+            self.number, self.glyph, self.name, self.strokes
+        )
+    }
+}
+
+// glyph, name and stroke count, the radical number is the position in the table.
+const KANGXI: [(char, &str, u32); 214] = [
+    ('一', "one", 1),
+    ('丨', "line", 1),
+    ('丶', "dot", 1),
+    ('丿', "slash", 1),
+    ('乙', "second", 1),
+    ('亅', "hook", 1),
+    ('二', "two", 2),
+    ('亠', "lid", 2),
+    ('人', "person", 2),
+    ('儿', "legs", 2),
+    ('入', "enter", 2),
+    ('八', "eight", 2),
+    ('冂', "down box", 2),
+    ('冖', "cover", 2),
+    ('冫', "ice", 2),
+    ('几', "table", 2),
+    ('凵', "open box", 2),
+    ('刀', "knife", 2),
+    ('力', "power", 2),
+    ('勹', "wrap", 2),
+    ('匕', "spoon", 2),
+    ('匚', "right open box", 2),
+    ('匸', "hiding enclosure", 2),
+    ('十', "ten", 2),
+    ('卜', "divination", 2),
+    ('卩', "seal", 2),
+    ('厂', "cliff", 2),
+    ('厶', "private", 2),
+    ('又', "again", 2),
+    ('口', "mouth", 3),
+    ('囗', "enclosure", 3),
+    ('土', "earth", 3),
+    ('士', "scholar", 3),
+    ('夂', "go", 3),
+    ('夊', "go slowly", 3),
+    ('夕', "evening", 3),
+    ('大', "big", 3),
+    ('女', "woman", 3),
+    ('子', "child", 3),
+    ('宀', "roof", 3),
+    ('寸', "inch", 3),
+    ('小', "small", 3),
+    ('尢', "lame", 3),
+    ('尸', "corpse", 3),
+    ('屮', "sprout", 3),
+    ('山', "mountain", 3),
+    ('巛', "river", 3),
+    ('工', "work", 3),
+    ('己', "oneself", 3),
+    ('巾', "turban", 3),
+    ('干', "dry", 3),
+    ('幺', "short thread", 3),
+    ('广', "dotted cliff", 3),
+    ('廴', "long stride", 3),
+    ('廾', "two hands", 3),
+    ('弋', "shoot", 3),
+    ('弓', "bow", 3),
+    ('彐', "snout", 3),
+    ('彡', "bristle", 3),
+    ('彳', "step", 3),
+    ('心', "heart", 4),
+    ('戈', "halberd", 4),
+    ('戶', "door", 4),
+    ('手', "hand", 4),
+    ('支', "branch", 4),
+    ('攴', "rap", 4),
+    ('文', "script", 4),
+    ('斗', "dipper", 4),
+    ('斤', "axe", 4),
+    ('方', "square", 4),
+    ('无', "not", 4),
+    ('日', "sun", 4),
+    ('曰', "say", 4),
+    ('月', "moon", 4),
+    ('木', "tree", 4),
+    ('欠', "lack", 4),
+    ('止', "stop", 4),
+    ('歹', "death", 4),
+    ('殳', "weapon", 4),
+    ('毋', "do not", 4),
+    ('比', "compare", 4),
+    ('毛', "fur", 4),
+    ('氏', "clan", 4),
+    ('气', "steam", 4),
+    ('水', "water", 4),
+    ('火', "fire", 4),
+    ('爪', "claw", 4),
+    ('父', "father", 4),
+    ('爻', "double x", 4),
+    ('爿', "half tree trunk", 4),
+    ('片', "slice", 4),
+    ('牙', "fang", 4),
+    ('牛', "cow", 4),
+    ('犬', "dog", 4),
+    ('玄', "profound", 5),
+    ('玉', "jade", 5),
+    ('瓜', "melon", 5),
+    ('瓦', "tile", 5),
+    ('甘', "sweet", 5),
+    ('生', "life", 5),
+    ('用', "use", 5),
+    ('田', "field", 5),
+    ('疋', "bolt of cloth", 5),
+    ('疒', "sickness", 5),
+    ('癶', "dotted tent", 5),
+    ('白', "white", 5),
+    ('皮', "skin", 5),
+    ('皿', "dish", 5),
+    ('目', "eye", 5),
+    ('矛', "spear", 5),
+    ('矢', "arrow", 5),
+    ('石', "stone", 5),
+    ('示', "spirit", 5),
+    ('禸', "track", 5),
+    ('禾', "grain", 5),
+    ('穴', "cave", 5),
+    ('立', "stand", 5),
+    ('竹', "bamboo", 6),
+    ('米', "rice", 6),
+    ('糸', "silk", 6),
+    ('缶', "jar", 6),
+    ('网', "net", 6),
+    ('羊', "sheep", 6),
+    ('羽', "feather", 6),
+    ('老', "old", 6),
+    ('而', "and", 6),
+    ('耒', "plow", 6),
+    ('耳', "ear", 6),
+    ('聿', "brush", 6),
+    ('肉', "meat", 6),
+    ('臣', "minister", 6),
+    ('自', "self", 6),
+    ('至', "arrive", 6),
+    ('臼', "mortar", 6),
+    ('舌', "tongue", 6),
+    ('舛', "oppose", 6),
+    ('舟', "boat", 6),
+    ('艮', "stopping", 6),
+    ('色', "color", 6),
+    ('艸', "grass", 6),
+    ('虍', "tiger", 6),
+    ('虫', "insect", 6),
+    ('血', "blood", 6),
+    ('行', "walk enclosure", 6),
+    ('衣', "clothes", 6),
+    ('襾', "west", 6),
+    ('見', "see", 7),
+    ('角', "horn", 7),
+    ('言', "speech", 7),
+    ('谷', "valley", 7),
+    ('豆', "bean", 7),
+    ('豕', "pig", 7),
+    ('豸', "badger", 7),
+    ('貝', "shell", 7),
+    ('赤', "red", 7),
+    ('走', "run", 7),
+    ('足', "foot", 7),
+    ('身', "body", 7),
+    ('車', "cart", 7),
+    ('辛', "bitter", 7),
+    ('辰', "morning", 7),
+    ('辵', "walk", 7),
+    ('邑', "city", 7),
+    ('酉', "wine", 7),
+    ('釆', "distinguish", 7),
+    ('里', "village", 7),
+    ('金', "gold", 8),
+    ('長', "long", 8),
+    ('門', "gate", 8),
+    ('阜', "mound", 8),
+    ('隶', "slave", 8),
+    ('隹', "short-tailed bird", 8),
+    ('雨', "rain", 8),
+    ('靑', "blue", 8),
+    ('非', "wrong", 8),
+    ('面', "face", 9),
+    ('革', "leather", 9),
+    ('韋', "tanned leather", 9),
+    ('韭', "leek", 9),
+    ('音', "sound", 9),
+    ('頁', "leaf", 9),
+    ('風', "wind", 9),
+    ('飛', "fly", 9),
+    ('食', "eat", 9),
+    ('首', "head", 9),
+    ('香', "fragrant", 9),
+    ('馬', "horse", 10),
+    ('骨', "bone", 10),
+    ('高', "tall", 10),
+    ('髟', "hair", 10),
+    ('鬥', "fight", 10),
+    ('鬯', "sacrificial wine", 10),
+    ('鬲', "cauldron", 10),
+    ('鬼', "ghost", 10),
+    ('魚', "fish", 11),
+    ('鳥', "bird", 11),
+    ('鹵', "salt", 11),
+    ('鹿', "deer", 11),
+    ('麥', "wheat", 11),
+    ('麻', "hemp", 11),
+    ('黃', "yellow", 12),
+    ('黍', "millet", 12),
+    ('黑', "black", 12),
+    ('黹', "embroidery", 12),
+    ('黽', "frog", 13),
+    ('鼎', "tripod", 13),
+    ('鼓', "drum", 13),
+    ('鼠', "rat", 13),
+    ('鼻', "nose", 14),
+    ('齊', "even", 14),
+    ('齒', "tooth", 15),
+    ('龍', "dragon", 16),
+    ('龜', "turtle", 16),
+    ('龠', "flute", 17),
+];
+
+pub fn radical(number: u32) -> Option<Radical> {
+    let (glyph, name, strokes) = *KANGXI.get((number as usize).checked_sub(1)?)?;
+    Some(Radical {
+        number,
+        glyph,
+        name,
+        strokes,
+    })
+}
+
+pub fn all_radicals() -> impl Iterator<Item = Radical> {
+    (1..=KANGXI.len() as u32).filter_map(radical)
+}
+
+// by number '85', glyph '水' or name 'water'.
+pub fn find_radical(query: &str) -> Option<Radical> {
+    let query = query.trim();
+    if let Ok(number) = query.parse() {
+        return radical(number);
+    }
+
+    all_radicals()
+        .find(|r| query.chars().eq(std::iter::once(r.glyph)) || r.name.eq_ignore_ascii_case(query))
+}
+
+#[cfg(test)]
+mod radicals_test {
+    use super::*;
+
+    #[test]
+    fn table() {
+        assert_eq!(all_radicals().count(), 214);
+        assert_eq!(radical(85).unwrap().glyph, '水');
+        assert_eq!(radical(214).unwrap().strokes, 17);
+        assert!(radical(0).is_none());
+        assert!(radical(215).is_none());
+
+        // stroke counts never go down along the table.
+        let strokes: Vec<u32> = all_radicals().map(|r| r.strokes).collect();
+        assert!(strokes.windows(2).all(|w| w[0] <= w[1]));
+
+        assert_eq!(find_radical("7").unwrap().glyph, '二');
+        assert_eq!(find_radical("口").unwrap().number, 30);
+        assert_eq!(find_radical("Water").unwrap().number, 85);
+        assert!(find_radical("nothing").is_none());
+    }
+}
@@ -0,0 +1,388 @@
+// katakana and hiragana blocks are laid out the same way, just shifted.
+const KATAKANA_TO_HIRAGANA: u32 = 0x60;
+
+pub fn katakana_to_hiragana(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'ァ'..='ヶ' => std::char::from_u32(c as u32 - KATAKANA_TO_HIRAGANA).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+pub fn hiragana_to_katakana(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'ぁ'..='ゖ' => std::char::from_u32(c as u32 + KATAKANA_TO_HIRAGANA).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+pub fn is_kana(c: char) -> bool {
+    matches!(c, 'ぁ'..='ゖ' | 'ァ'..='ヺ' | 'ー')
+}
+
+// KANJIDIC2 kun readings mark okurigana with a dot and affixes with a dash: '-つ.ぐ'.
+// returns every spelling that counts as that reading: the whole word 'つぐ' and its stem 'つ'.
+pub fn reading_variants(reading: &str) -> Vec<String> {
+    let reading = katakana_to_hiragana(reading.trim_matches('-'));
+    match reading.split_once('.') {
+        Some((stem, okurigana)) => vec![format!("{}{}", stem, okurigana), stem.to_string()],
+        None => vec![reading],
+    }
+}
+
+// both Hepburn and Kunrei spellings, longest ones have to be tried first.
+const SYLLABLES: &[(&str, &str)] = &[
+    ("xtsu", "っ"),
+    ("ltsu", "っ"),
+    ("shi", "し"),
+    ("sha", "しゃ"),
+    ("shu", "しゅ"),
+    ("she", "しぇ"),
+    ("sho", "しょ"),
+    ("chi", "ち"),
+    ("cha", "ちゃ"),
+    ("chu", "ちゅ"),
+    ("che", "ちぇ"),
+    ("cho", "ちょ"),
+    ("tsu", "つ"),
+    ("xtu", "っ"),
+    ("ltu", "っ"),
+    ("kya", "きゃ"),
+    ("kyu", "きゅ"),
+    ("kyo", "きょ"),
+    ("sya", "しゃ"),
+    ("syu", "しゅ"),
+    ("syo", "しょ"),
+    ("tya", "ちゃ"),
+    ("tyu", "ちゅ"),
+    ("tyo", "ちょ"),
+    ("cya", "ちゃ"),
+    ("cyu", "ちゅ"),
+    ("cyo", "ちょ"),
+    ("nya", "にゃ"),
+    ("nyu", "にゅ"),
+    ("nyo", "にょ"),
+    ("hya", "ひゃ"),
+    ("hyu", "ひゅ"),
+    ("hyo", "ひょ"),
+    ("mya", "みゃ"),
+    ("myu", "みゅ"),
+    ("myo", "みょ"),
+    ("rya", "りゃ"),
+    ("ryu", "りゅ"),
+    ("ryo", "りょ"),
+    ("gya", "ぎゃ"),
+    ("gyu", "ぎゅ"),
+    ("gyo", "ぎょ"),
+    ("zya", "じゃ"),
+    ("zyu", "じゅ"),
+    ("zyo", "じょ"),
+    ("jya", "じゃ"),
+    ("jyu", "じゅ"),
+    ("jyo", "じょ"),
+    ("dya", "ぢゃ"),
+    ("dyu", "ぢゅ"),
+    ("dyo", "ぢょ"),
+    ("bya", "びゃ"),
+    ("byu", "びゅ"),
+    ("byo", "びょ"),
+    ("pya", "ぴゃ"),
+    ("pyu", "ぴゅ"),
+    ("pyo", "ぴょ"),
+    ("xya", "ゃ"),
+    ("xyu", "ゅ"),
+    ("xyo", "ょ"),
+    ("lya", "ゃ"),
+    ("lyu", "ゅ"),
+    ("lyo", "ょ"),
+    ("ka", "か"),
+    ("ki", "き"),
+    ("ku", "く"),
+    ("ke", "け"),
+    ("ko", "こ"),
+    ("sa", "さ"),
+    ("si", "し"),
+    ("su", "す"),
+    ("se", "せ"),
+    ("so", "そ"),
+    ("ta", "た"),
+    ("ti", "ち"),
+    ("tu", "つ"),
+    ("te", "て"),
+    ("to", "と"),
+    ("na", "な"),
+    ("ni", "に"),
+    ("nu", "ぬ"),
+    ("ne", "ね"),
+    ("no", "の"),
+    ("ha", "は"),
+    ("hi", "ひ"),
+    ("fu", "ふ"),
+    ("hu", "ふ"),
+    ("he", "へ"),
+    ("ho", "ほ"),
+    ("fa", "ふぁ"),
+    ("fi", "ふぃ"),
+    ("fe", "ふぇ"),
+    ("fo", "ふぉ"),
+    ("ma", "ま"),
+    ("mi", "み"),
+    ("mu", "む"),
+    ("me", "め"),
+    ("mo", "も"),
+    ("ya", "や"),
+    ("yu", "ゆ"),
+    ("yo", "よ"),
+    ("ra", "ら"),
+    ("ri", "り"),
+    ("ru", "る"),
+    ("re", "れ"),
+    ("ro", "ろ"),
+    ("wa", "わ"),
+    ("wi", "ゐ"),
+    ("we", "ゑ"),
+    ("wo", "を"),
+    ("ga", "が"),
+    ("gi", "ぎ"),
+    ("gu", "ぐ"),
+    ("ge", "げ"),
+    ("go", "ご"),
+    ("za", "ざ"),
+    ("zi", "じ"),
+    ("ji", "じ"),
+    ("zu", "ず"),
+    ("ze", "ぜ"),
+    ("zo", "ぞ"),
+    ("ja", "じゃ"),
+    ("ju", "じゅ"),
+    ("je", "じぇ"),
+    ("jo", "じょ"),
+    ("da", "だ"),
+    ("di", "ぢ"),
+    ("du", "づ"),
+    ("de", "で"),
+    ("do", "ど"),
+    ("ba", "ば"),
+    ("bi", "び"),
+    ("bu", "ぶ"),
+    ("be", "べ"),
+    ("bo", "ぼ"),
+    ("pa", "ぱ"),
+    ("pi", "ぴ"),
+    ("pu", "ぷ"),
+    ("pe", "ぺ"),
+    ("po", "ぽ"),
+    ("vu", "ゔ"),
+    ("xa", "ぁ"),
+    ("xi", "ぃ"),
+    ("xu", "ぅ"),
+    ("xe", "ぇ"),
+    ("xo", "ぉ"),
+    ("la", "ぁ"),
+    ("li", "ぃ"),
+    ("lu", "ぅ"),
+    ("le", "ぇ"),
+    ("lo", "ぉ"),
+    ("n'", "ん"),
+    ("a", "あ"),
+    ("i", "い"),
+    ("u", "う"),
+    ("e", "え"),
+    ("o", "お"),
+];
+
+// long vowels of Hepburn (macron) and Kunrei (circumflex), spelled the way KANJIDIC2 does: こう, not こー.
+fn expand_long_vowel(c: char) -> Option<&'static str> {
+    match c {
+        'ā' | 'â' => Some("aa"),
+        'ī' | 'î' => Some("ii"),
+        'ū' | 'û' => Some("uu"),
+        'ē' | 'ê' => Some("ee"),
+        'ō' | 'ô' => Some("ou"),
+        _ => None,
+    }
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'i' | 'u' | 'e' | 'o')
+}
+
+// romaji -> kana, syllables typed in upper case become katakana (the way IMEs do it with shift).
+// 'finished' tells whether the input is complete, while typing an unfinished syllable like 'ky' or
+// a trailing 'n' is left as it is since the next letter may still change it.
+pub fn romaji_to_kana(input: &str, finished: bool) -> String {
+    // (lowercase letter, typed in upper case)
+    let mut chars: Vec<(char, bool)> = Vec::new();
+    for c in input.chars() {
+        let upper = c.is_uppercase();
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        match expand_long_vowel(lower) {
+            Some(expanded) => chars.extend(expanded.chars().map(|e| (e, upper))),
+            None => chars.push((lower, upper)),
+        }
+    }
+
+    let mut result = String::new();
+    let push = |result: &mut String, kana: &str, upper: bool| {
+        if upper {
+            result.push_str(&hiragana_to_katakana(kana))
+        } else {
+            result.push_str(kana)
+        }
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let (c, upper) = chars[i];
+        let next = chars.get(i + 1).map(|(n, _)| *n);
+
+        // doubled consonant is a small tsu: 'kk', and Hepburn 'tch'.
+        let doubled = next == Some(c) || (c == 't' && next == Some('c'));
+        if c.is_ascii_lowercase() && !is_vowel(c) && c != 'n' && c != 'm' && doubled {
+            push(&mut result, "っ", upper);
+            i += 1;
+            continue;
+        }
+
+        // 'nn' is a syllabic n, unless it is Hepburn 'n' + 'na' like in 'konnichi'.
+        if c == 'n' && next == Some('n') {
+            let after = chars.get(i + 2).map(|(n, _)| *n);
+            push(&mut result, "ん", upper);
+            i += match after {
+                Some(a) if is_vowel(a) || a == 'y' => 1,
+                _ => 2,
+            };
+            continue;
+        }
+
+        // syllabic n: before a consonant other than y, or at the very end. Hepburn writes it as 'm' before b/m/p.
+        if c == 'n' || c == 'm' {
+            let nasal_before = |n: char| match c {
+                'n' => !is_vowel(n) && n != 'y' && n != '\'',
+                _ => n == 'b' || n == 'm' || n == 'p',
+            };
+            match next {
+                Some(n) if nasal_before(n) => {
+                    push(&mut result, "ん", upper);
+                    i += 1;
+                    continue;
+                }
+                None if c == 'n' && finished => {
+                    push(&mut result, "ん", upper);
+                    i += 1;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        let matched = SYLLABLES.iter().find(|(romaji, _)| {
+            let len = romaji.chars().count();
+            i + len <= chars.len()
+                && romaji
+                    .chars()
+                    .zip(chars[i..i + len].iter())
+                    .all(|(r, (c, _))| r == *c)
+        });
+
+        match matched {
+            Some((romaji, kana)) => {
+                push(&mut result, kana, upper);
+                i += romaji.chars().count();
+            }
+            None => {
+                result.push(if upper {
+                    c.to_uppercase().next().unwrap_or(c)
+                } else {
+                    c
+                });
+                i += 1;
+            }
+        }
+    }
+
+    result
+}
+
+// converted input, None if anything was left that is not kana (so it wasnt romaji of a reading at all).
+pub fn romaji_to_reading(input: &str) -> Option<String> {
+    let converted = romaji_to_kana(input.trim(), true);
+    if !converted.is_empty()
+        && converted
+            .chars()
+            .all(|c| is_kana(c) || c == '.' || c == '-')
+    {
+        Some(converted)
+    } else {
+        None
+    }
+}
+
+// reads a line from the terminal, romaji gets converted to kana as it is being typed.
+pub fn read_kana_line(term: &console::Term, prompt: &str) -> std::io::Result<String> {
+    use console::Key;
+
+    let mut typed = String::new();
+    loop {
+        term.clear_line()?;
+        term.write_str(&format!("{}{}", prompt, romaji_to_kana(&typed, false)))?;
+        match term.read_key()? {
+            Key::Enter => {
+                term.write_line("")?;
+                return Ok(romaji_to_kana(&typed, true));
+            }
+            Key::Backspace => {
+                typed.pop();
+            }
+            Key::Char(c) if !c.is_control() => typed.push(c),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod kana_test {
+    use super::*;
+
+    #[test]
+    fn readings() {
+        assert_eq!(katakana_to_hiragana("アク"), "あく");
+        assert_eq!(katakana_to_hiragana("つ.ぐ"), "つ.ぐ");
+        assert_eq!(reading_variants("つ.ぐ"), vec!["つぐ", "つ"]);
+        assert_eq!(reading_variants("-あ.がる"), vec!["あがる", "あ"]);
+        assert_eq!(reading_variants("ア"), vec!["あ"]);
+    }
+
+    #[test]
+    fn romaji() {
+        let hiragana = |s| romaji_to_kana(s, true);
+        assert_eq!(hiragana("tsu.gu"), "つ.ぐ");
+        assert_eq!(hiragana("tu.gu"), "つ.ぐ");
+        assert_eq!(hiragana("shinbun"), "しんぶん");
+        assert_eq!(hiragana("shimbun"), "しんぶん");
+        assert_eq!(hiragana("sammai"), "さんまい");
+        assert_eq!(hiragana("sinbun"), "しんぶん");
+        assert_eq!(hiragana("kin'en"), "きんえん");
+        assert_eq!(hiragana("kinen"), "きねん");
+        assert_eq!(hiragana("gakkou"), "がっこう");
+        assert_eq!(hiragana("matcha"), "まっちゃ");
+        assert_eq!(hiragana("tōkyō"), "とうきょう");
+        assert_eq!(hiragana("tôkyô"), "とうきょう");
+        assert_eq!(hiragana("zyuu"), "じゅう");
+        assert_eq!(hiragana("jū"), "じゅう");
+        assert_eq!(hiragana("hon"), "ほん");
+        assert_eq!(hiragana("konnichiha"), "こんにちは");
+        assert_eq!(hiragana("honn"), "ほん");
+        assert_eq!(hiragana("KOU"), "コウ");
+        assert_eq!(hiragana("AKU"), "アク");
+
+        assert_eq!(romaji_to_kana("hon", false), "ほn");
+        assert_eq!(romaji_to_kana("ky", false), "ky");
+
+        assert_eq!(romaji_to_reading("a"), Some("あ".to_string()));
+        assert_eq!(romaji_to_reading("come after"), None);
+    }
+}
@@ -1,5 +1,3 @@
-use serde_xml_rs as serde_xml;
-
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -34,6 +32,34 @@ pub struct Misc {
     pub jlpt: Option<u32>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DicRef {
+    // heisig, heisig6, henshall, gakken, ...
+    pub dr_type: String,
+    #[serde(rename = "$value")]
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DicNumber {
+    pub dic_ref: Vec<DicRef>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QCode {
+    // skip, sh_desc, four_corner, deroo, misclass
+    pub qc_type: String,
+    // set on SKIP codes people commonly get wrong: posn, stroke_count, stroke_and_posn, stroke_diff
+    pub skip_misclass: Option<String>,
+    #[serde(rename = "$value")]
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueryCode {
+    pub q_code: Vec<QCode>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Reading {
     // important ones are ja_on and ja_kun
@@ -64,6 +90,8 @@ pub struct RmGroup {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ReadingMeaning {
     pub rmgroup: RmGroup,
+    // readings used only in names.
+    pub nanori: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -72,6 +100,9 @@ pub struct Character {
     pub codepoint: CodePoint,
     pub radical: Radical,
     pub misc: Misc,
+    // index numbers in dictionaries and textbooks.
+    pub dic_number: Option<DicNumber>,
+    pub query_code: Option<QueryCode>,
     pub reading_meaning: Option<ReadingMeaning>,
 }
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -214,4 +245,97 @@ mod kanji_tests {
         let first = x.character.first().unwrap();
         assert_eq!('亜', first.literal);
     }
+
+    // a character with just the required parts and whatever the test is about.
+    fn character(literal: char, rest: &str) -> Character {
+        let s = format!(
+            r#"
+<kanjidic2>
+<character>
+<literal>{literal}</literal>
+<codepoint>
+<cp_value cp_type="ucs">{code:x}</cp_value>
+</codepoint>
+<radical>
+<rad_value rad_type="classical">1</rad_value>
+</radical>
+<misc>
+<stroke_count>7</stroke_count>
+</misc>{rest}
+</character>
+</kanjidic2>
+"#,
+            literal = literal,
+            code = literal as u32,
+            rest = rest
+        );
+        let mut x: KanjiDictionary = serde_xml::from_str(&s).unwrap();
+        x.character.remove(0)
+    }
+
+    #[test]
+    fn nanori_deser() {
+        let named = character(
+            '亜',
+            r#"
+<reading_meaning>
+<rmgroup>
+<reading r_type="ja_on">ア</reading>
+<meaning>Asia</meaning>
+</rmgroup>
+<nanori>や</nanori>
+<nanori>つぎ</nanori>
+<nanori>つぐ</nanori>
+</reading_meaning>"#,
+        );
+        let nanori = named.reading_meaning.as_ref().unwrap().nanori.as_ref();
+        assert_eq!(nanori.unwrap(), &vec!["や", "つぎ", "つぐ"]);
+
+        let unnamed = character(
+            '唖',
+            r#"
+<reading_meaning>
+<rmgroup>
+<meaning>mute</meaning>
+</rmgroup>
+</reading_meaning>"#,
+        );
+        assert!(unnamed.reading_meaning.unwrap().nanori.is_none());
+    }
+
+    #[test]
+    fn dic_number_deser() {
+        let c = character(
+            '亜',
+            r#"
+<dic_number>
+<dic_ref dr_type="heisig">1809</dic_ref>
+<dic_ref dr_type="heisig6">1950</dic_ref>
+<dic_ref dr_type="moro" m_vol="1" m_page="0525">272</dic_ref>
+</dic_number>"#,
+        );
+        let dic_refs = &c.dic_number.as_ref().unwrap().dic_ref;
+        let heisig6 = dic_refs.iter().find(|r| r.dr_type == "heisig6").unwrap();
+        assert_eq!(heisig6.value, "1950");
+        assert!(character('唖', "").dic_number.is_none());
+    }
+
+    #[test]
+    fn query_code_deser() {
+        let c = character(
+            '亜',
+            r#"
+<query_code>
+<q_code qc_type="skip">4-7-1</q_code>
+<q_code qc_type="skip" skip_misclass="posn">1-1-6</q_code>
+<q_code qc_type="four_corner">1010.6</q_code>
+</query_code>"#,
+        );
+        let q_codes = &c.query_code.as_ref().unwrap().q_code;
+        assert_eq!(q_codes.len(), 3);
+        assert_eq!(q_codes[0].value, "4-7-1");
+        assert!(q_codes[0].skip_misclass.is_none());
+        assert_eq!(q_codes[1].skip_misclass.as_deref(), Some("posn"));
+        assert_eq!(q_codes[2].qc_type, "four_corner");
+    }
 }
@@ -0,0 +1,120 @@
+use super::{KanjiDrawRecipe, RenderOptions, CANVAS_SIZE, SVG_TAIL};
+
+// how long the finished kanji stays up before the animation starts over, in seconds.
+const FINISHED_HOLD: f64 = 1.5;
+
+fn percent(at: f64, total: f64) -> String {
+    format!("{:.2}%", at / total * 100.0)
+}
+
+impl KanjiDrawRecipe {
+    // css animation, each stroke draws itself by shrinking its dash offset from the full length to 0.
+    // pathLength="1" only takes the dash math off the estimated length, which still decides how long
+    // every stroke takes at drawing_speed: long strokes draw for longer than short ones.
+    // the whole kanji is there in light gray underneath, so the learner sees where it is going.
+    pub fn generate_animated_svg(&self, options: &RenderOptions) -> Result<String, String> {
+        let speed = options.drawing_speed.max(1.0);
+        let pause = options.stroke_pause.max(0.0);
+
+        let mut timings = Vec::new();
+        let mut at = 0.0;
+        for p in self.paths() {
+            let duration = p.length()? / speed;
+            timings.push((at, at + duration));
+            at += duration + pause;
+        }
+        // no pause after the last stroke.
+        let total = (at - pause).max(0.0) + FINISHED_HOLD;
+
+        let font = options.font(options.font_size);
+        let mut style = String::new();
+        let mut ghost = String::new();
+        let mut strokes = String::new();
+        for (index, (p, (start, end))) in self.paths().iter().zip(timings).enumerate() {
+            let (x, y) = p.extract_x_y()?;
+            let color = options.palette.color(index);
+            style += &format!(
+                r#"
+@keyframes stroke{index} {{ 0%, {start} {{ stroke-dashoffset: 1; }} {end}, 100% {{ stroke-dashoffset: 0; }} }}
+@keyframes number{index} {{ 0% {{ opacity: 0; }} {start}, 100% {{ opacity: 1; }} }}
+#stroke{index} {{ stroke-dasharray: 1; animation: stroke{index} {total:.2}s linear infinite; }}
+#number{index} {{ animation: number{index} {total:.2}s step-end infinite; }}"#,
+                index = index + 1,
+                start = percent(start, total),
+                end = percent(end, total),
+                total = total,
+            );
+            ghost += &format!(
+                r#"
+<path style="fill:none;stroke:lightgray;stroke-width:{}" d="{}"/>"#,
+                options.stroke_width, p.d
+            );
+            strokes += &format!(
+                r#"
+<path id="stroke{index}" pathLength="1" style="fill:none;stroke:{color};stroke-width:{width}" d="{d}"/>"#,
+                index = index + 1,
+                color = color,
+                width = options.stroke_width,
+                d = p.d,
+            );
+            if options.show_numbers {
+                strokes += &format!(
+                    r#"
+<text id="number{index}" x="{x}" y="{y}" style="fill:{color}" {font}>{index}</text>"#,
+                    index = index + 1,
+                    x = x,
+                    y = y,
+                    color = color,
+                    font = font,
+                );
+            }
+        }
+
+        Ok(format!(
+            "{header}\n<style>{style}\n</style>{ghost}{strokes}\n{tail}",
+            header = options.svg_header(CANVAS_SIZE, CANVAS_SIZE),
+            style = style,
+            ghost = ghost,
+            strokes = strokes,
+            tail = SVG_TAIL
+        ))
+    }
+}
+
+#[cfg(test)]
+mod animated_svg_test {
+    use super::*;
+    use crate::kanji_strokes::{test_two, Palette, StrokeView};
+
+    #[test]
+    fn strokes_draw_in_order() {
+        let two = test_two();
+        let options = RenderOptions {
+            view: StrokeView::Animated,
+            drawing_speed: 50.0,
+            stroke_pause: 1.0,
+            ..RenderOptions::default()
+        };
+        let svg = two.generate_animated_svg(&options).unwrap();
+
+        // 1s for the first stroke, 1s pause, 1.8s for the second one and 1.5s hold: 5.3s in total.
+        assert!(svg.contains(
+            "#stroke1 { stroke-dasharray: 1; animation: stroke1 5.30s linear infinite; }"
+        ));
+        // 50 long first stroke draws over 1s, 18.87% of the loop, the 90 long second one over 1.8s.
+        assert!(svg.contains("@keyframes stroke1 { 0%, 0.00% { stroke-dashoffset: 1; } 18.87%, 100% { stroke-dashoffset: 0; } }"));
+        assert!(svg.contains("@keyframes stroke2 { 0%, 37.74% { stroke-dashoffset: 1; } 71.70%, 100% { stroke-dashoffset: 0; } }"));
+        assert_eq!(svg.matches("stroke:lightgray").count(), 2);
+
+        let plain = RenderOptions {
+            palette: Palette::ColorblindSafe,
+            show_numbers: false,
+            ..options
+        };
+        let svg = two.generate_animated_svg(&plain).unwrap();
+        assert!(svg.contains(
+            r##"<path id="stroke2" pathLength="1" style="fill:none;stroke:#56B4E9;stroke-width:2""##
+        ));
+        assert!(!svg.contains("<text"));
+    }
+}
@@ -0,0 +1,156 @@
+use super::{KanjiDrawRecipe, Palette, RenderOptions, CANVAS_SIZE, SVG_TAIL};
+
+const FRAME_SIZE: f64 = CANVAS_SIZE;
+
+impl KanjiDrawRecipe {
+    // one frame per stroke, each one adds the next stroke in red on top of the black ones before.
+    // monochrome prints the newest one black on gray ones instead, other palettes use their first color.
+    pub fn generate_filmstrip_svg(&self, options: &RenderOptions) -> Result<String, String> {
+        let (newest, earlier) = match &options.palette {
+            Palette::Classic => ("red", "black"),
+            Palette::Monochrome => ("black", "gray"),
+            palette => (palette.color(0), "black"),
+        };
+        let label_size = options.font_size * 1.6;
+        let font = options.font(label_size);
+        // frames are small, strokes get drawn a little bolder.
+        let stroke_width = options.stroke_width + 1.0;
+        let frames = self.paths().len();
+        let per_row = options.frames_per_row.max(1);
+        let columns = per_row.min(frames).max(1);
+        let rows = frames.div_ceil(per_row).max(1);
+        let width = columns as f64 * FRAME_SIZE;
+        let height = rows as f64 * FRAME_SIZE;
+
+        let mut body = String::new();
+        for frame in 0..frames {
+            let x = (frame % per_row) as f64 * FRAME_SIZE;
+            let y = (frame / per_row) as f64 * FRAME_SIZE;
+            body += &format!(
+                r#"
+<g transform="translate({},{})">"#,
+                x, y
+            );
+
+            if options.grid_lines {
+                let half = FRAME_SIZE / 2.0;
+                body += &format!(
+                    r#"
+<rect x="0.5" y="0.5" width="{inner}" height="{inner}" style="fill:none;stroke:gray;stroke-width:1"/>
+<line x1="{half}" y1="0" x2="{half}" y2="{size}" style="stroke:lightgray;stroke-width:0.5;stroke-dasharray:3,3"/>
+<line x1="0" y1="{half}" x2="{size}" y2="{half}" style="stroke:lightgray;stroke-width:0.5;stroke-dasharray:3,3"/>"#,
+                    inner = FRAME_SIZE - 1.0,
+                    half = half,
+                    size = FRAME_SIZE,
+                );
+            }
+
+            for (index, p) in self.paths()[..=frame].iter().enumerate() {
+                let color = if index == frame { newest } else { earlier };
+                body += &format!(
+                    r#"
+<path style="fill:none;stroke:{};stroke-width:{};stroke-linecap:round" d="{}"/>"#,
+                    color, stroke_width, p.d
+                );
+            }
+
+            if options.show_numbers {
+                let (start_x, start_y) = self.paths()[frame].extract_x_y()?;
+                let number = (frame + 1).to_string();
+                // digits are about 0.6 em wide.
+                let label_width = number.len() as f64 * label_size * 0.6;
+                // right aligned just left of where the stroke starts, pushed back inside the frame near its edges.
+                let x = (start_x - stroke_width / 2.0 - 1.0).clamp(label_width, FRAME_SIZE);
+                let y = start_y.clamp(label_size / 2.0, FRAME_SIZE - label_size / 2.0);
+                body += &format!(
+                    r#"
+<text x="{}" y="{}" text-anchor="end" dominant-baseline="middle" style="fill:{}" {}>{}</text>"#,
+                    x, y, newest, font, number
+                );
+            }
+            body += "\n</g>";
+        }
+
+        Ok(format!(
+            "{}{}\n{}",
+            options.svg_header(width, height),
+            body,
+            SVG_TAIL
+        ))
+    }
+}
+
+#[cfg(test)]
+mod filmstrip_svg_test {
+    use super::*;
+    use crate::kanji_strokes::test_recipe;
+
+    #[test]
+    fn frame_per_stroke() {
+        let three = test_recipe('三', &["M30,25 l50,0", "M35,55 l40,0", "M5,90 l90,0"]);
+
+        let options = RenderOptions {
+            frames_per_row: 2,
+            grid_lines: false,
+            ..RenderOptions::default()
+        };
+        let svg = three.generate_filmstrip_svg(&options).unwrap();
+        assert!(svg.starts_with(r#"<svg width="218" height="218""#));
+        assert!(svg.contains(r#"<g transform="translate(0,109)">"#));
+        // 1 + 2 + 3 strokes drawn, the newest one of every frame in red.
+        assert_eq!(svg.matches("<path").count(), 6);
+        assert_eq!(svg.matches("stroke:red").count(), 3);
+        assert!(!svg.contains("<line"));
+        assert!(svg.contains(r#"<text x="27.5" y="25" text-anchor="end""#));
+        // the third stroke starts at the left edge, its number stays inside the frame.
+        assert!(svg.contains(r#"<text x="4.8" y="90" text-anchor="end""#));
+
+        let in_a_row = RenderOptions {
+            frames_per_row: 10,
+            grid_lines: true,
+            ..RenderOptions::default()
+        };
+        let svg = three.generate_filmstrip_svg(&in_a_row).unwrap();
+        assert!(svg.starts_with(r#"<svg width="327" height="109""#));
+        assert_eq!(svg.matches("<rect").count(), 3);
+
+        let printable = RenderOptions {
+            palette: Palette::Monochrome,
+            size: 218,
+            show_numbers: false,
+            ..in_a_row
+        };
+        let svg = three.generate_filmstrip_svg(&printable).unwrap();
+        assert!(svg.starts_with(r#"<svg width="654" height="218" viewBox="0 0 327 109""#));
+        assert!(!svg.contains("red"));
+        assert!(!svg.contains("<text"));
+
+        let colorblind = RenderOptions {
+            palette: Palette::ColorblindSafe,
+            ..RenderOptions::default()
+        };
+        let svg = three.generate_filmstrip_svg(&colorblind).unwrap();
+        assert_eq!(svg.matches("stroke:#E69F00").count(), 3);
+        assert!(!svg.contains("red"));
+    }
+
+    #[test]
+    fn numbers_stay_in_frame() {
+        let corners = test_recipe('口', &["M1,1 l50,0", "M108,108 l0,-50"]);
+        let svg = corners
+            .generate_filmstrip_svg(&RenderOptions::default())
+            .unwrap();
+        // 8 wide numbers, one digit is 4.8 wide.
+        assert!(svg.contains(r#"<text x="4.8" y="4" text-anchor="end""#));
+        assert!(svg.contains(r#"<text x="105.5" y="105" text-anchor="end""#));
+
+        let large = RenderOptions {
+            font_size: 10.0,
+            stroke_width: 5.0,
+            ..RenderOptions::default()
+        };
+        let svg = corners.generate_filmstrip_svg(&large).unwrap();
+        assert!(svg.contains(r#"<text x="9.6" y="8" text-anchor="end""#));
+        assert!(svg.contains(r#"<text x="104" y="101" text-anchor="end""#));
+    }
+}
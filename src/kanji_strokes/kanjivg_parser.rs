@@ -1,38 +1,161 @@
-use serde_xml_rs as serde_xml;
+use std::collections::BTreeSet;
 
 use serde::{Deserialize, Serialize};
 
+use super::path_data::{
+    bounding_box, parse_path_data, to_absolute_cubics, Cubic, PathCommand, Point,
+};
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Path {
     pub d: String,
+    // kvg:type, the CJK stroke it is: '㇒', '㇏', '㇑a', ...
+    #[serde(rename = "type")]
+    pub stroke_type: Option<String>,
 }
 
 impl Path {
-    pub fn extract_x_y(&self) -> (f64, f64) {
-        let mut split = self.d.split(',');
-        let first: &str = split.next().expect("no first coordinate?");
-        let second: &str = split.next().expect("no second coordinate?");
-        let first = &first[1..]; // pop first character which is 'M' always.
-        let x = first
-            .parse::<f64>()
-            .expect("Couldnt parse out x coordinate");
-        let position_of_c = second
-            .chars()
-            .position(|c| c == 'c' || c == 'C' || c == ' ')
-            .unwrap_or(second.len());
-        let y = second[..position_of_c]
-            .parse::<f64>()
-            .expect("couldnt parse out y coordinate");
-        (x, y)
+    pub fn commands(&self) -> Result<Vec<PathCommand>, String> {
+        parse_path_data(&self.d).map_err(|e| format!("{} in path '{}'", e, self.d))
+    }
+
+    pub fn cubics(&self) -> Result<Vec<Cubic>, String> {
+        Ok(to_absolute_cubics(&self.commands()?))
+    }
+
+    // where the stroke starts.
+    pub fn extract_x_y(&self) -> Result<(f64, f64), String> {
+        match self.commands()?.first() {
+            // a leading relative move is relative to the origin, so the same thing.
+            Some(PathCommand::MoveTo { to, .. }) => Ok((to.x, to.y)),
+            _ => Err(format!("path '{}' does not start with a move", self.d)),
+        }
+    }
+
+    // where the stroke starts and which way it heads off from there, as a unit vector.
+    pub fn start_direction(&self) -> Result<(Point, Point), String> {
+        let cubics = self.cubics()?;
+        let first = cubics
+            .first()
+            .ok_or_else(|| format!("path '{}' draws nothing", self.d))?;
+        // a first control point sitting on the start makes the tangent there zero, look a bit further then.
+        let tangent = [0.0, 0.01, 0.1, 0.5]
+            .iter()
+            .map(|t| first.tangent_at(*t))
+            .find(|d| d.x.hypot(d.y) > 1e-9)
+            .ok_or_else(|| format!("path '{}' does not go anywhere", self.d))?;
+        let length = tangent.x.hypot(tangent.y);
+        Ok((
+            first.from,
+            Point::new(tangent.x / length, tangent.y / length),
+        ))
+    }
+}
+
+impl Path {
+    pub fn length(&self) -> Result<f64, String> {
+        Ok(self.cubics()?.iter().map(|c| c.length()).sum())
+    }
+
+    pub fn bounds(&self) -> Result<(Point, Point), String> {
+        let corners: Vec<Point> = self
+            .cubics()?
+            .iter()
+            .flat_map(|c| {
+                let (min, max) = c.bounds();
+                vec![min, max]
+            })
+            .collect();
+        bounding_box(&corners).ok_or_else(|| format!("path '{}' draws nothing", self.d))
+    }
+
+    // points along the stroke no more than about step apart, both ends included.
+    pub fn sample(&self, step: f64) -> Result<Vec<Point>, String> {
+        let cubics = self.cubics()?;
+        let mut points: Vec<Point> = cubics.first().map(|c| c.from).into_iter().collect();
+        for c in &cubics {
+            let steps = (c.length() / step).ceil().max(1.0) as usize;
+            points.extend((1..=steps).map(|i| c.point_at(i as f64 / steps as f64)));
+        }
+        Ok(points)
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct KanjiGroup {
+    pub id: Option<String>,
+    // kvg:element, the component this group draws.
+    pub element: Option<String>,
+    // kvg:original, the full form of a squashed element: 人 for 亻.
+    pub original: Option<String>,
+    // kvg:position, where the component sits: left, right, top, bottom, kamae, ...
+    pub position: Option<String>,
+    // kvg:radical, which dictionaries file the kanji under this one: general, tradit, nelson.
+    pub radical: Option<String>,
+    // kvg:part, the element is drawn in pieces split by other components, this is the n-th one.
+    pub part: Option<u32>,
     #[serde(rename = "$value")]
     pub children: Option<Vec<KanjiGroupElems>>,
 }
 
+// what a kanji or its component is drawn from, with the group attributes KanjiVG has.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Component {
+    pub element: Option<String>,
+    pub original: Option<String>,
+    pub position: Option<String>,
+    pub radical: Option<String>,
+    pub part: Option<u32>,
+    // all strokes the component takes, its children's included.
+    pub stroke_count: usize,
+    pub children: Vec<Component>,
+}
+
+impl Component {
+    fn from_group(g: &KanjiGroup) -> Component {
+        let mut stroke_count = 0;
+        let mut children = Vec::new();
+        for c in g.children.iter().flatten() {
+            match c {
+                KanjiGroupElems::G(g) => {
+                    let child = Component::from_group(g);
+                    stroke_count += child.stroke_count;
+                    children.push(child);
+                }
+                KanjiGroupElems::Path(_) => stroke_count += 1,
+            }
+        }
+
+        Component {
+            element: g.element.clone(),
+            original: g.original.clone(),
+            position: g.position.clone(),
+            radical: g.radical.clone(),
+            part: g.part,
+            stroke_count,
+            children,
+        }
+    }
+
+    // one line about the component itself, children not included: '亻 (人) left, radical: general'.
+    pub fn describe(&self) -> String {
+        let mut described = self.element.clone().unwrap_or_else(|| "?".to_string());
+        if let Some(original) = &self.original {
+            described += &format!(" ({})", original);
+        }
+        if let Some(position) = &self.position {
+            described += &format!(" {}", position);
+        }
+        if let Some(part) = self.part {
+            described += &format!(" part {}", part);
+        }
+        if let Some(radical) = &self.radical {
+            described += &format!(", radical: {}", radical);
+        }
+        described + &format!(", strokes: {}", self.stroke_count)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Kanji {
     pub id: String,
@@ -53,6 +176,39 @@ impl Kanji {
         result
     }
 
+    pub fn decompose(&self) -> Component {
+        Component::from_group(&self.g)
+    }
+
+    // every component drawn somewhere inside the kanji, not counting the kanji itself.
+    pub fn extract_elements(&self) -> BTreeSet<char> {
+        let mut result = BTreeSet::new();
+        if let Some(children) = &self.g.children {
+            for c in children {
+                if let KanjiGroupElems::G(g) = c {
+                    Self::extract_elements_impl(g, &mut result);
+                }
+            }
+        }
+        result
+    }
+
+    fn extract_elements_impl(g: &KanjiGroup, elements_so_far: &mut BTreeSet<char>) {
+        // elements are single characters, anything longer is not a component we can look up.
+        let mut element = g.element.iter().flat_map(|e| e.chars());
+        if let (Some(e), None) = (element.next(), element.next()) {
+            elements_so_far.insert(e);
+        }
+
+        if let Some(children) = &g.children {
+            for c in children {
+                if let KanjiGroupElems::G(g) = c {
+                    Self::extract_elements_impl(g, elements_so_far);
+                }
+            }
+        }
+    }
+
     fn extract_subpaths_impl(g: &KanjiGroup, paths_so_far: &mut Vec<Path>) {
         if let Some(children) = &g.children {
             for c in children {
@@ -137,7 +293,7 @@ mod kanjivg_parser_test {
         let x_ys: Vec<_> = second
             .extract_subpaths()
             .into_iter()
-            .map(|p| p.extract_x_y())
+            .map(|p| p.extract_x_y().unwrap())
             .collect();
 
         // 		<path id="kvg:04e2a-s1" kvg:type="㇒" d="M52.75,10.25c0.11,1.12,0,3.49-0.72,4.99C47.5,24.75,34.25,45,14.25,57.75"/>
@@ -148,4 +304,93 @@ mod kanjivg_parser_test {
         let expected_x_ys = vec![(52.75, 10.25), (51.75, 15.75), (51.87, 46.25)];
         assert_eq!(x_ys, expected_x_ys);
     }
+
+    // 个 with its two components.
+    fn ge() -> Kanji {
+        let example = r#"
+<kanjivg xmlns:kvg='http://kanjivg.tagaini.net'>
+<kanji id="kvg:kanji_04e2a">
+<g id="kvg:04e2a" kvg:element="个">
+	<g id="kvg:04e2a-g1" kvg:element="人" kvg:position="top" kvg:radical="nelson">
+		<path id="kvg:04e2a-s1" kvg:type="㇒" d="M52.75,10.25c0.11,1.12,0,3.49-0.72,4.99C47.5,24.75,34.25,45,14.25,57.75"/>
+		<path id="kvg:04e2a-s2" kvg:type="㇏" d="M51.75,15.75c5.92,7.28,31.44,31.07,37.97,36.4c2.22,1.81,5.06,2.58,7.28,3.1"/>
+	</g>
+	<g id="kvg:04e2a-g2" kvg:element="丨" kvg:position="bottom" kvg:radical="tradit">
+		<path id="kvg:04e2a-s3" kvg:type="㇑" d="M51.87,46.25c1.09,0.5,1.74,2.25,1.96,3.25c0.22,1,0,43.25-0.22,49.5"/>
+	</g>
+</g>
+</kanji>
+</kanjivg>
+        "#;
+        let mut x: Kanjivg = serde_xml::from_str(example).unwrap();
+        x.kanji.remove(0)
+    }
+
+    fn path(d: &str) -> Path {
+        Path {
+            d: d.to_string(),
+            stroke_type: None,
+        }
+    }
+
+    #[test]
+    fn elements_deser() {
+        let ge = ge();
+        assert_eq!(ge.g.element.as_deref(), Some("个"));
+        assert_eq!(
+            ge.extract_elements().into_iter().collect::<Vec<_>>(),
+            vec!['丨', '人']
+        );
+
+        let tree = ge.decompose();
+        assert_eq!(tree.element.as_deref(), Some("个"));
+        assert_eq!(tree.stroke_count, 3);
+        assert_eq!(tree.children.len(), 2);
+        assert_eq!(tree.children[0].position.as_deref(), Some("top"));
+        assert_eq!(tree.children[1].radical.as_deref(), Some("tradit"));
+        assert_eq!(tree.children[1].stroke_count, 1);
+        assert_eq!(
+            tree.children[0].describe(),
+            "人 top, radical: nelson, strokes: 2"
+        );
+    }
+
+    #[test]
+    fn stroke_type_deser() {
+        let types: Vec<_> = ge()
+            .extract_subpaths()
+            .into_iter()
+            .map(|p| p.stroke_type)
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                Some("㇒".to_string()),
+                Some("㇏".to_string()),
+                Some("㇑".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn path_data() {
+        // the dot of '!' is written with spaces, the comma split used to choke on it.
+        let dot = path("M54.5,88 c -0.83,0 -1.5,0.67 -1.5,1.5 0,0.83 0.67,1.5 1.5,1.5 0.83,0 1.5,-0.67 1.5,-1.5 0,-0.83 -0.67,-1.5 -1.5,-1.5");
+        assert_eq!(dot.extract_x_y().unwrap(), (54.5, 88.0));
+        let (min, max) = dot.bounds().unwrap();
+        assert!((min.x - 53.0).abs() < 1e-9 && (max.x - 56.0).abs() < 1e-9);
+        assert!(dot.length().unwrap() > 9.0);
+        let samples = dot.sample(1.0).unwrap();
+        assert_eq!(samples.first(), Some(&Point::new(54.5, 88.0)));
+        assert!(samples.len() > 9);
+
+        assert!(path("M 54.5").extract_x_y().is_err());
+    }
+
+    #[test]
+    fn start_direction() {
+        let (start, direction) = ge().extract_subpaths()[2].start_direction().unwrap();
+        assert_eq!(start, Point::new(51.87, 46.25));
+        assert!(direction.x > 0.8 && direction.y > 0.3);
+    }
 }
@@ -1,9 +1,12 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
 
 use super::kanjivg_parser;
-use crate::kanji_strokes::Kanjivg;
+use super::path_data::Point;
+use crate::kanji_strokes::{Kanjivg, RenderOptions, CANVAS_SIZE};
+
+pub const SVG_TAIL: &str = r#"</svg>"#;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct KanjiDrawRecipe {
@@ -11,113 +14,159 @@ pub struct KanjiDrawRecipe {
 }
 
 impl KanjiDrawRecipe {
-    fn color_table() -> [&'static str; 74] {
-        [
-            "darkmagenta",
-            "darkolivegreen",
-            "darkorange",
-            "darkorchid",
-            "darkred",
-            "darksalmon",
-            "darkseagreen",
-            "darkslateblue",
-            "darkslategray",
-            "darkslategrey",
-            "darkturquoise",
-            "darkviolet",
-            "deeppink",
-            "deepskyblue",
-            "dimgray",
-            "dimgrey",
-            "dodgerblue",
-            "firebrick",
-            "floralwhite",
-            "forestgreen",
-            "fuchsia",
-            "gainsboro",
-            "ghostwhite",
-            "gold",
-            "goldenrod",
-            "gray",
-            "green",
-            "greenyellow",
-            "grey",
-            "honeydew",
-            "hotpink",
-            "indianred",
-            "indigo",
-            "ivory",
-            "khaki",
-            "lavender",
-            "lavenderblush",
-            "lawngreen",
-            "lemonchiffon",
-            "lightblue",
-            "lightcoral",
-            "lightcyan",
-            "lightgoldenrodyellow",
-            "lightgray",
-            "lightgreen",
-            "lightgrey",
-            "lightpink",
-            "lightsalmon",
-            "lightseagreen",
-            "lightskyblue",
-            "lightslategray",
-            "lightslategrey",
-            "lightsteelblue",
-            "lightyellow",
-            "lime",
-            "limegreen",
-            "linen",
-            "magenta",
-            "maroon",
-            "mediumaquamarine",
-            "mediumblue",
-            "mediumorchid",
-            "mediumpurple",
-            "mediumseagreen",
-            "mediumslateblue",
-            "mediumspringgreen",
-            "mediumturquoise",
-            "mediumvioletred",
-            "midnightblue",
-            "mintcream",
-            "mistyrose",
-            "moccasin",
-            "navajowhite",
-            "navy",
-        ]
-    }
-    pub fn generate_svg(&self) -> String {
-        let header = r#"<svg width="109" height="109" viewBox="0 0 109 109" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" xml:space="preserve" version="1.1"  baseProfile="full">"#;
-        let tail = r#"</svg>"#;
+    pub fn generate_svg(&self, options: &RenderOptions) -> Result<String, String> {
+        let header = options.svg_header(CANVAS_SIZE, CANVAS_SIZE);
+        let tail = SVG_TAIL;
+        let font = options.font(options.font_size);
+
+        let starts = self
+            .paths
+            .iter()
+            .map(|p| p.start_direction())
+            .collect::<Result<Vec<_>, _>>()?;
+        // start dots and arrowheads of every stroke, numbers keep clear of them.
+        let mut markers = Vec::new();
+        if options.direction_markers {
+            for (start, direction) in &starts {
+                markers.push(*start);
+                markers.push(Point::new(
+                    start.x + direction.x * ARROWHEAD_CENTER,
+                    start.y + direction.y * ARROWHEAD_CENTER,
+                ));
+            }
+        }
 
         let mut body = String::new();
-        for (index, p) in self.paths.iter().enumerate() {
-            let p: &kanjivg_parser::Path = p;
-            let (x, y) = p.extract_x_y();
+        let mut placed_labels = Vec::new();
+        for (index, (p, &(start, direction))) in self.paths.iter().zip(&starts).enumerate() {
+            let (color, width) = match options.highlight {
+                Some(highlighted) if highlighted == index => ("black", options.stroke_width + 1.0),
+                Some(_) => ("lightgray", options.stroke_width),
+                None => (options.palette.color(index), options.stroke_width),
+            };
+
             body = format!(
                 r#"{}
-<path style="fill:none;stroke:{color};stroke-width:2" d="{}"/>
-<text x="{x}" y="{y}" style="stroke:black" font-size="5">{index}</text>
-<text x="{x}" y="{y}" style="fill:{color}" font-size="5">{index}</text>"#,
+<path style="fill:none;stroke:{color};stroke-width:{width}" d="{}"/>"#,
                 body,
                 p.d,
-                x = x,
-                y = y,
-                color = Self::color_table()[index],
-                index = index + 1,
-            )
+                color = color,
+                width = width,
+            );
+            let label = place_label(start, direction, &placed_labels, &markers);
+            if options.show_numbers {
+                placed_labels.push(label);
+                body = format!(
+                    r#"{}
+<text x="{x:.2}" y="{y:.2}" text-anchor="middle" dominant-baseline="middle" style="stroke:black" {font}>{index}</text>
+<text x="{x:.2}" y="{y:.2}" text-anchor="middle" dominant-baseline="middle" style="fill:{color}" {font}>{index}</text>"#,
+                    body,
+                    x = label.x,
+                    y = label.y,
+                    font = font,
+                    color = color,
+                    index = index + 1,
+                );
+            }
+
+            if options.direction_markers {
+                body += &direction_marker(start, direction, color);
+            }
+
+            if let Some(stroke_type) = p
+                .stroke_type
+                .as_ref()
+                .filter(|_| options.stroke_type_labels)
+            {
+                // right after the number, wherever that ended up.
+                let at = Point::new(label.x + 4.0, label.y);
+                placed_labels.push(at);
+                body = format!(
+                    r#"{}
+<text x="{x:.2}" y="{y:.2}" dominant-baseline="middle" style="fill:dimgray" {font}>{}</text>"#,
+                    body,
+                    stroke_type,
+                    font = options.font(options.font_size * 0.8),
+                    x = at.x,
+                    y = at.y,
+                );
+            }
         }
 
-        format!(
+        Ok(format!(
             "{header}{body}\n{tail}",
             header = header,
             body = body,
             tail = tail
-        )
+        ))
+    }
+
+    pub fn paths(&self) -> &[kanjivg_parser::Path] {
+        &self.paths
+    }
+}
+
+// numbers closer than this to each other are hard to tell apart.
+const LABEL_SPACING: f64 = 5.0;
+// a number closer than this to a start dot or an arrowhead covers it.
+const MARKER_CLEARANCE: f64 = 3.5;
+// how far along the stroke the arrowhead sits, halfway between its base and tip.
+const ARROWHEAD_CENTER: f64 = 6.0;
+
+// behind the start if there is room, to the sides or further out if a number or a marker is there already.
+fn place_label(start: Point, direction: Point, placed: &[Point], markers: &[Point]) -> Point {
+    let behind = Point::new(-direction.x, -direction.y);
+    let left = Point::new(direction.y, -direction.x);
+    let right = Point::new(-direction.y, direction.x);
+    let at = |side: Point, distance: f64| {
+        Point::new(start.x + side.x * distance, start.y + side.y * distance)
+    };
+
+    for distance in [4.0, 7.0, 10.0].iter() {
+        for side in [behind, left, right].iter() {
+            let candidate = at(*side, *distance);
+            if placed
+                .iter()
+                .all(|p| p.distance(candidate) >= LABEL_SPACING)
+                && markers
+                    .iter()
+                    .all(|m| m.distance(candidate) >= MARKER_CLEARANCE)
+            {
+                return candidate;
+            }
+        }
     }
+    at(behind, 4.0)
+}
+
+// a dot on the start and an arrowhead a little way along the initial tangent.
+fn direction_marker(start: Point, direction: Point, color: &str) -> String {
+    let along = |distance: f64, sideways: f64| {
+        Point::new(
+            start.x + direction.x * distance - direction.y * sideways,
+            start.y + direction.y * distance + direction.x * sideways,
+        )
+    };
+    let tip = along(ARROWHEAD_CENTER + 2.0, 0.0);
+    let (left, right) = (
+        along(ARROWHEAD_CENTER - 2.0, 2.0),
+        along(ARROWHEAD_CENTER - 2.0, -2.0),
+    );
+
+    format!(
+        r#"
+<circle cx="{}" cy="{}" r="1.5" style="fill:{color}"/>
+<polygon points="{:.2},{:.2} {:.2},{:.2} {:.2},{:.2}" style="fill:{color}"/>"#,
+        start.x,
+        start.y,
+        tip.x,
+        tip.y,
+        left.x,
+        left.y,
+        right.x,
+        right.y,
+        color = color,
+    )
 }
 
 #[derive(Clone, Debug)]
@@ -125,28 +174,30 @@ pub struct Strokes {
     pub dict: BTreeMap<char, KanjiDrawRecipe>,
 }
 
-pub fn parse_kanjivg() -> kanjivg_parser::Kanjivg {
+pub fn parse_kanjivg() -> Kanjivg {
     serde_xml_rs::from_reader(
         std::fs::File::open("kanjivg.xml").expect("Couldnt open kanjivg file"),
     )
     .expect("Couldnt parse kanjivg struct!")
 }
 
-pub fn kanjivg_into_strokes(kanjivg: &kanjivg_parser::Kanjivg) -> Strokes {
+fn literal_of(k: &kanjivg_parser::Kanji) -> char {
+    let unicode =
+        k.id.strip_prefix("kvg:kanji_")
+            .expect("didt not contain kanji_ prefix?");
+    u32::from_str_radix(unicode, 16)
+        .ok()
+        .and_then(std::char::from_u32)
+        .expect("couldnt parse out unicode")
+}
+
+pub fn kanjivg_into_strokes(kanjivg: &Kanjivg) -> Strokes {
     let dict = kanjivg
         .kanji
         .iter()
         .map(|k: &kanjivg_parser::Kanji| {
-            let unicode =
-                k.id.strip_prefix("kvg:kanji_")
-                    .expect("didt not contain kanji_ prefix?");
-            let kanji = u32::from_str_radix(unicode, 16)
-                .ok()
-                .and_then(std::char::from_u32)
-                .expect("couldnt parse out unicode");
-
             (
-                kanji,
+                literal_of(k),
                 KanjiDrawRecipe {
                     paths: k.extract_subpaths(),
                 },
@@ -157,9 +208,29 @@ pub fn kanjivg_into_strokes(kanjivg: &kanjivg_parser::Kanjivg) -> Strokes {
     Strokes { dict }
 }
 
+pub fn kanjivg_into_decompositions(kanjivg: &Kanjivg) -> BTreeMap<char, kanjivg_parser::Component> {
+    kanjivg
+        .kanji
+        .iter()
+        .map(|k| (literal_of(k), k.decompose()))
+        .collect()
+}
+
+// kanji -> components it is drawn from, for when there is no KRADFILE around.
+pub fn kanjivg_into_components(kanjivg: &Kanjivg) -> BTreeMap<char, BTreeSet<char>> {
+    kanjivg
+        .kanji
+        .iter()
+        .map(|k| (literal_of(k), k.extract_elements()))
+        .filter(|(_, elements)| !elements.is_empty())
+        .collect()
+}
+
 #[cfg(test)]
-mod kanjivg_strokes {
+mod kanjivg_strokes_test {
     use super::*;
+    use crate::kanji_strokes::{test_recipe, Palette};
+
     #[test]
     fn kanji_stroke_test() {
         let parsed : kanjivg_parser::Kanjivg = serde_xml_rs::from_str(r#"
@@ -185,17 +256,120 @@ mod kanjivg_strokes {
         let expected = r#"
 <svg width="109" height="109" viewBox="0 0 109 109" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" xml:space="preserve" version="1.1"  baseProfile="full">
 <path style="fill:none;stroke:darkmagenta;stroke-width:2" d="M52.75,10.25c0.11,1.12,0,3.49-0.72,4.99C47.5,24.75,34.25,45,14.25,57.75"/>
-<text x="52.75" y="10.25" style="stroke:black" font-size="5">1</text>
-<text x="52.75" y="10.25" style="fill:darkmagenta" font-size="5">1</text>
+<text x="52.36" y="6.27" text-anchor="middle" dominant-baseline="middle" style="stroke:black" font-size="5">1</text>
+<text x="52.36" y="6.27" text-anchor="middle" dominant-baseline="middle" style="fill:darkmagenta" font-size="5">1</text>
 <path style="fill:none;stroke:darkolivegreen;stroke-width:2" d="M51.75,15.75c5.92,7.28,31.44,31.07,37.97,36.4c2.22,1.81,5.06,2.58,7.28,3.1"/>
-<text x="51.75" y="15.75" style="stroke:black" font-size="5">2</text>
-<text x="51.75" y="15.75" style="fill:darkolivegreen" font-size="5">2</text>
+<text x="49.23" y="12.65" text-anchor="middle" dominant-baseline="middle" style="stroke:black" font-size="5">2</text>
+<text x="49.23" y="12.65" text-anchor="middle" dominant-baseline="middle" style="fill:darkolivegreen" font-size="5">2</text>
 <path style="fill:none;stroke:darkorange;stroke-width:2" d="M51.87,46.25c1.09,0.5,1.74,2.25,1.96,3.25c0.22,1,0,43.25-0.22,49.5"/>
-<text x="51.87" y="46.25" style="stroke:black" font-size="5">3</text>
-<text x="51.87" y="46.25" style="fill:darkorange" font-size="5">3</text>
+<text x="48.23" y="44.58" text-anchor="middle" dominant-baseline="middle" style="stroke:black" font-size="5">3</text>
+<text x="48.23" y="44.58" text-anchor="middle" dominant-baseline="middle" style="fill:darkorange" font-size="5">3</text>
 </svg>
         "#.trim();
 
-        assert_eq!(draw_recipe.generate_svg(), expected)
+        assert_eq!(
+            draw_recipe.generate_svg(&RenderOptions::default()).unwrap(),
+            expected
+        );
+    }
+
+    fn ge() -> KanjiDrawRecipe {
+        test_recipe(
+            '个',
+            &[
+                "M52.75,10.25c0.11,1.12,0,3.49-0.72,4.99C47.5,24.75,34.25,45,14.25,57.75",
+                "M51.75,15.75c5.92,7.28,31.44,31.07,37.97,36.4c2.22,1.81,5.06,2.58,7.28,3.1",
+                "M51.87,46.25c1.09,0.5,1.74,2.25,1.96,3.25c0.22,1,0,43.25-0.22,49.5",
+            ],
+        )
+    }
+
+    #[test]
+    fn stroke_type_labels() {
+        let labeled = ge()
+            .generate_svg(&RenderOptions {
+                stroke_type_labels: true,
+                ..RenderOptions::default()
+            })
+            .unwrap();
+        // right after the number.
+        assert!(labeled.contains(
+            r#"<text x="56.36" y="6.27" dominant-baseline="middle" style="fill:dimgray" font-size="4">㇐</text>"#
+        ));
+        assert_eq!(labeled.matches("style=\"fill:dimgray\"").count(), 3);
+    }
+
+    #[test]
+    fn highlighted_stroke() {
+        let highlighted = ge()
+            .generate_svg(&RenderOptions {
+                highlight: Some(1),
+                ..RenderOptions::default()
+            })
+            .unwrap();
+        assert!(highlighted.contains(r#"style="fill:none;stroke:black;stroke-width:3" d="M51.75"#));
+        assert_eq!(highlighted.matches("stroke:lightgray").count(), 2);
+    }
+
+    #[test]
+    fn direction_markers() {
+        let marked = ge()
+            .generate_svg(&RenderOptions {
+                direction_markers: true,
+                ..RenderOptions::default()
+            })
+            .unwrap();
+        assert_eq!(marked.matches("<circle").count(), 3);
+        assert_eq!(marked.matches("<polygon").count(), 3);
+        assert!(
+            marked.contains(r#"<circle cx="52.75" cy="10.25" r="1.5" style="fill:darkmagenta"/>"#)
+        );
+        // the first two strokes start 5.6 apart, number 2 still clears the first arrowhead.
+        assert!(marked.contains(r#"<text x="49.23" y="12.65""#));
+    }
+
+    #[test]
+    fn labels_keep_clear_of_markers() {
+        // a marker right behind the start sends the number to the side.
+        let start = Point::new(50.0, 50.0);
+        let right = Point::new(1.0, 0.0);
+        assert_eq!(place_label(start, right, &[], &[]), Point::new(46.0, 50.0));
+        let blocked = place_label(start, right, &[], &[Point::new(45.0, 50.0)]);
+        assert_eq!(blocked, Point::new(50.0, 46.0));
+        // so does another number.
+        let taken = place_label(start, right, &[Point::new(47.0, 51.0)], &[]);
+        assert_eq!(taken, Point::new(50.0, 46.0));
+    }
+
+    #[test]
+    fn type_labels_follow_moved_numbers() {
+        let marked_and_typed = ge()
+            .generate_svg(&RenderOptions {
+                direction_markers: true,
+                stroke_type_labels: true,
+                ..RenderOptions::default()
+            })
+            .unwrap();
+        assert!(marked_and_typed.contains(r#"<text x="56.36" y="6.27" dominant-baseline="middle""#));
+        assert_eq!(
+            marked_and_typed
+                .matches(r#"dominant-baseline="middle" style="fill:dimgray""#)
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn themed() {
+        let themed = ge()
+            .generate_svg(&RenderOptions {
+                stroke_width: 3.5,
+                palette: Palette::Monochrome,
+                show_numbers: false,
+                ..RenderOptions::default()
+            })
+            .unwrap();
+        assert_eq!(themed.matches("stroke:black;stroke-width:3.5").count(), 3);
+        assert!(!themed.contains("<text"));
     }
 }
@@ -1,5 +1,52 @@
+mod animated_svg;
+mod filmstrip_svg;
 mod kanjivg_parser;
 mod kanjivg_strokes;
+mod path_data;
+mod render_options;
+mod stroke_types;
+mod terminal_strokes;
 
 pub use kanjivg_parser::*;
 pub use kanjivg_strokes::*;
+pub use render_options::*;
+pub use stroke_types::*;
+
+// a one kanji KanjiVG file made of the given horizontal strokes, for the renderer tests.
+#[cfg(test)]
+pub fn test_recipe(literal: char, strokes: &[&str]) -> KanjiDrawRecipe {
+    let code = format!("{:05x}", literal as u32);
+    let paths: String = strokes
+        .iter()
+        .enumerate()
+        .map(|(i, d)| {
+            format!(
+                r#"
+	<path id="kvg:{code}-s{n}" kvg:type="㇐" d="{d}"/>"#,
+                code = code,
+                n = i + 1,
+                d = d
+            )
+        })
+        .collect();
+    let xml = format!(
+        r#"
+<kanjivg xmlns:kvg='http://kanjivg.tagaini.net'>
+<kanji id="kvg:kanji_{code}">
+<g id="kvg:{code}" kvg:element="{literal}">{paths}
+</g>
+</kanji>
+</kanjivg>"#,
+        code = code,
+        literal = literal,
+        paths = paths
+    );
+    let parsed: Kanjivg = serde_xml_rs::from_str(&xml).unwrap();
+    kanjivg_into_strokes(&parsed).dict.remove(&literal).unwrap()
+}
+
+// 二, a short one and a long one.
+#[cfg(test)]
+pub fn test_two() -> KanjiDrawRecipe {
+    test_recipe('二', &["M30,30 l50,0", "M10,80 L100,80"])
+}
@@ -0,0 +1,430 @@
+// SVG path data as KanjiVG writes it: 'M52.75,10.25c0.11,1.12,0,3.49-0.72,4.99C47.5,24.75,...'.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Point {
+        Point { x, y }
+    }
+
+    fn offset(self, relative: bool, from: Point) -> Point {
+        if relative {
+            Point::new(from.x + self.x, from.y + self.y)
+        } else {
+            self
+        }
+    }
+
+    fn lerp(self, to: Point, t: f64) -> Point {
+        Point::new(self.x + (to.x - self.x) * t, self.y + (to.y - self.y) * t)
+    }
+
+    pub fn distance(self, to: Point) -> f64 {
+        (to.x - self.x).hypot(to.y - self.y)
+    }
+}
+
+// relative ones carry offsets from the current point, absolute ones coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo {
+        relative: bool,
+        to: Point,
+    },
+    LineTo {
+        relative: bool,
+        to: Point,
+    },
+    CurveTo {
+        relative: bool,
+        ctrl1: Point,
+        ctrl2: Point,
+        to: Point,
+    },
+    // first control point is the previous curve's second one mirrored.
+    SmoothCurveTo {
+        relative: bool,
+        ctrl2: Point,
+        to: Point,
+    },
+    ClosePath,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cubic {
+    pub from: Point,
+    pub ctrl1: Point,
+    pub ctrl2: Point,
+    pub to: Point,
+}
+
+// straight pieces get approximated by this many chords when measuring.
+const LENGTH_STEPS: usize = 32;
+
+impl Cubic {
+    fn line(from: Point, to: Point) -> Cubic {
+        Cubic {
+            from,
+            ctrl1: from.lerp(to, 1.0 / 3.0),
+            ctrl2: from.lerp(to, 2.0 / 3.0),
+            to,
+        }
+    }
+
+    pub fn point_at(&self, t: f64) -> Point {
+        let a = self.from.lerp(self.ctrl1, t);
+        let b = self.ctrl1.lerp(self.ctrl2, t);
+        let c = self.ctrl2.lerp(self.to, t);
+        a.lerp(b, t).lerp(b.lerp(c, t), t)
+    }
+
+    // direction of travel, not normalized.
+    pub fn tangent_at(&self, t: f64) -> Point {
+        let d = |p0: f64, p1: f64, p2: f64, p3: f64| {
+            let u = 1.0 - t;
+            3.0 * u * u * (p1 - p0) + 6.0 * u * t * (p2 - p1) + 3.0 * t * t * (p3 - p2)
+        };
+        Point::new(
+            d(self.from.x, self.ctrl1.x, self.ctrl2.x, self.to.x),
+            d(self.from.y, self.ctrl1.y, self.ctrl2.y, self.to.y),
+        )
+    }
+
+    pub fn length(&self) -> f64 {
+        (1..=LENGTH_STEPS)
+            .map(|i| {
+                let t0 = (i - 1) as f64 / LENGTH_STEPS as f64;
+                let t1 = i as f64 / LENGTH_STEPS as f64;
+                self.point_at(t0).distance(self.point_at(t1))
+            })
+            .sum()
+    }
+
+    // (top left, bottom right), exact: endpoints plus wherever the curve turns around.
+    pub fn bounds(&self) -> (Point, Point) {
+        let mut ts = vec![0.0, 1.0];
+        ts.extend(extremes(self.from.x, self.ctrl1.x, self.ctrl2.x, self.to.x));
+        ts.extend(extremes(self.from.y, self.ctrl1.y, self.ctrl2.y, self.to.y));
+
+        let points: Vec<Point> = ts.into_iter().map(|t| self.point_at(t)).collect();
+        bounding_box(&points).expect("there are always the endpoints")
+    }
+}
+
+// where the derivative of one coordinate of a cubic is zero, inside of (0, 1).
+fn extremes(p0: f64, p1: f64, p2: f64, p3: f64) -> Vec<f64> {
+    let a = -p0 + 3.0 * p1 - 3.0 * p2 + p3;
+    let b = 2.0 * (p0 - 2.0 * p1 + p2);
+    let c = p1 - p0;
+
+    let roots = if a.abs() < 1e-12 {
+        if b.abs() < 1e-12 {
+            Vec::new()
+        } else {
+            vec![-c / b]
+        }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            Vec::new()
+        } else {
+            let sqrt = discriminant.sqrt();
+            vec![(-b + sqrt) / (2.0 * a), (-b - sqrt) / (2.0 * a)]
+        }
+    };
+    roots.into_iter().filter(|t| *t > 0.0 && *t < 1.0).collect()
+}
+
+pub fn bounding_box(points: &[Point]) -> Option<(Point, Point)> {
+    let first = *points.first()?;
+    Some(points.iter().fold((first, first), |(min, max), p| {
+        (
+            Point::new(min.x.min(p.x), min.y.min(p.y)),
+            Point::new(max.x.max(p.x), max.y.max(p.y)),
+        )
+    }))
+}
+
+struct Tokenizer<'a> {
+    d: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn skip_separators(&mut self) {
+        while self.position < self.d.len()
+            && (self.d[self.position].is_ascii_whitespace() || self.d[self.position] == b',')
+        {
+            self.position += 1;
+        }
+    }
+
+    fn peek_command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        self.d
+            .get(self.position)
+            .copied()
+            .filter(|c| c.is_ascii_alphabetic() && *c != b'e' && *c != b'E')
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.position >= self.d.len()
+    }
+
+    // '-0.72', '.5', '1e-3', a sign or a second dot starts the next number: '4.99-0.72', '.5.5'.
+    fn number(&mut self) -> Result<f64, String> {
+        self.skip_separators();
+        let start = self.position;
+        let digits = |t: &mut Self| {
+            let from = t.position;
+            while t.position < t.d.len() && t.d[t.position].is_ascii_digit() {
+                t.position += 1;
+            }
+            t.position > from
+        };
+
+        if self.position < self.d.len() && matches!(self.d[self.position], b'+' | b'-') {
+            self.position += 1;
+        }
+        let mut has_digits = digits(self);
+        if self.position < self.d.len() && self.d[self.position] == b'.' {
+            self.position += 1;
+            has_digits |= digits(self);
+        }
+        if !has_digits {
+            return Err(format!("expected a number at {}", start));
+        }
+        if self.position < self.d.len() && matches!(self.d[self.position], b'e' | b'E') {
+            self.position += 1;
+            if self.position < self.d.len() && matches!(self.d[self.position], b'+' | b'-') {
+                self.position += 1;
+            }
+            if !digits(self) {
+                return Err(format!("expected an exponent at {}", self.position));
+            }
+        }
+
+        std::str::from_utf8(&self.d[start..self.position])
+            .expect("only ascii got consumed")
+            .parse()
+            .map_err(|_| format!("invalid number at {}", start))
+    }
+
+    fn point(&mut self) -> Result<Point, String> {
+        Ok(Point::new(self.number()?, self.number()?))
+    }
+}
+
+pub fn parse_path_data(d: &str) -> Result<Vec<PathCommand>, String> {
+    let mut tokens = Tokenizer {
+        d: d.as_bytes(),
+        position: 0,
+    };
+    let mut commands = Vec::new();
+    let mut current = None;
+
+    while !tokens.at_end() {
+        // numbers without a command letter repeat the previous one, after a move they are lines.
+        let command = match tokens.peek_command() {
+            Some(c) => {
+                tokens.position += 1;
+                c
+            }
+            None => match current {
+                Some(b'M') => b'L',
+                Some(b'm') => b'l',
+                Some(b'Z') | Some(b'z') | None => {
+                    return Err(format!("expected a command at {}", tokens.position))
+                }
+                Some(c) => c,
+            },
+        };
+        if commands.is_empty() && !matches!(command, b'M' | b'm') {
+            return Err("path data has to start with a move".to_string());
+        }
+
+        let relative = command.is_ascii_lowercase();
+        commands.push(match command.to_ascii_uppercase() {
+            b'M' => PathCommand::MoveTo {
+                relative,
+                to: tokens.point()?,
+            },
+            b'L' => PathCommand::LineTo {
+                relative,
+                to: tokens.point()?,
+            },
+            b'C' => PathCommand::CurveTo {
+                relative,
+                ctrl1: tokens.point()?,
+                ctrl2: tokens.point()?,
+                to: tokens.point()?,
+            },
+            b'S' => PathCommand::SmoothCurveTo {
+                relative,
+                ctrl2: tokens.point()?,
+                to: tokens.point()?,
+            },
+            b'Z' => PathCommand::ClosePath,
+            _ => {
+                return Err(format!(
+                    "unsupported command '{}' at {}",
+                    command as char,
+                    tokens.position - 1
+                ))
+            }
+        });
+        current = Some(command);
+    }
+
+    if commands.is_empty() {
+        return Err("path data is empty".to_string());
+    }
+    Ok(commands)
+}
+
+// everything drawn as absolute cubics, moves only start a new piece.
+pub fn to_absolute_cubics(commands: &[PathCommand]) -> Vec<Cubic> {
+    let mut cubics = Vec::new();
+    let mut current = Point::new(0.0, 0.0);
+    let mut subpath_start = current;
+    // second control point of the previous curve, smooth curves mirror it.
+    let mut last_ctrl2: Option<Point> = None;
+
+    for command in commands {
+        let mut ctrl2 = None;
+        match *command {
+            PathCommand::MoveTo { relative, to } => {
+                current = to.offset(relative, current);
+                subpath_start = current;
+            }
+            PathCommand::LineTo { relative, to } => {
+                let to = to.offset(relative, current);
+                cubics.push(Cubic::line(current, to));
+                current = to;
+            }
+            PathCommand::CurveTo {
+                relative,
+                ctrl1,
+                ctrl2: c2,
+                to,
+            } => {
+                let cubic = Cubic {
+                    from: current,
+                    ctrl1: ctrl1.offset(relative, current),
+                    ctrl2: c2.offset(relative, current),
+                    to: to.offset(relative, current),
+                };
+                cubics.push(cubic);
+                ctrl2 = Some(cubic.ctrl2);
+                current = cubic.to;
+            }
+            PathCommand::SmoothCurveTo {
+                relative,
+                ctrl2: c2,
+                to,
+            } => {
+                let ctrl1 = last_ctrl2
+                    .map(|c| Point::new(2.0 * current.x - c.x, 2.0 * current.y - c.y))
+                    .unwrap_or(current);
+                let cubic = Cubic {
+                    from: current,
+                    ctrl1,
+                    ctrl2: c2.offset(relative, current),
+                    to: to.offset(relative, current),
+                };
+                cubics.push(cubic);
+                ctrl2 = Some(cubic.ctrl2);
+                current = cubic.to;
+            }
+            PathCommand::ClosePath => {
+                if current != subpath_start {
+                    cubics.push(Cubic::line(current, subpath_start));
+                }
+                current = subpath_start;
+            }
+        }
+        last_ctrl2 = ctrl2;
+    }
+
+    cubics
+}
+
+#[cfg(test)]
+mod path_data_test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let commands = parse_path_data("M54.5,88 c -0.83,0 -1.5,0.67 -1.5,1.5").unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                PathCommand::MoveTo {
+                    relative: false,
+                    to: Point::new(54.5, 88.0)
+                },
+                PathCommand::CurveTo {
+                    relative: true,
+                    ctrl1: Point::new(-0.83, 0.0),
+                    ctrl2: Point::new(-1.5, 0.67),
+                    to: Point::new(-1.5, 1.5)
+                },
+            ]
+        );
+
+        // numbers glued together by signs and dots, implicit lines after a move.
+        let commands = parse_path_data("M 1 2 3 4c0.11,1.12,0,3.49-0.72,4.99s.5.5,1e1-1Z").unwrap();
+        assert_eq!(commands.len(), 5);
+        assert_eq!(
+            commands[1],
+            PathCommand::LineTo {
+                relative: false,
+                to: Point::new(3.0, 4.0)
+            }
+        );
+        assert_eq!(
+            commands[3],
+            PathCommand::SmoothCurveTo {
+                relative: true,
+                ctrl2: Point::new(0.5, 0.5),
+                to: Point::new(10.0, -1.0)
+            }
+        );
+
+        assert!(parse_path_data("").is_err());
+        assert!(parse_path_data("L1,2").is_err());
+        assert!(parse_path_data("M1").is_err());
+        assert!(parse_path_data("M1,2 Q1,2,3,4").is_err());
+        assert!(parse_path_data("M1,x").is_err());
+    }
+
+    #[test]
+    fn cubics() {
+        let commands = parse_path_data("M10,10 l10,0 c0,5 5,10 10,10 S30,40 40,40 z").unwrap();
+        let cubics = to_absolute_cubics(&commands);
+        assert_eq!(cubics.len(), 4);
+        assert_eq!(cubics[0].to, Point::new(20.0, 10.0));
+        assert_eq!(cubics[1].ctrl2, Point::new(25.0, 20.0));
+        assert_eq!(cubics[2].ctrl1, Point::new(35.0, 20.0));
+        assert_eq!(cubics[3].to, Point::new(10.0, 10.0));
+
+        assert!((cubics[0].length() - 10.0).abs() < 1e-9);
+        let (min, max) = cubics[0].bounds();
+        assert_eq!((min, max), (Point::new(10.0, 10.0), Point::new(20.0, 10.0)));
+
+        // a curve bulging out past its endpoints.
+        let bulge = Cubic {
+            from: Point::new(0.0, 0.0),
+            ctrl1: Point::new(0.0, 10.0),
+            ctrl2: Point::new(10.0, 10.0),
+            to: Point::new(10.0, 0.0),
+        };
+        assert!((bulge.bounds().1.y - 7.5).abs() < 1e-9);
+        assert_eq!(bulge.point_at(0.5), Point::new(5.0, 7.5));
+    }
+}
@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrokeView {
+    // every stroke at once with its number.
+    #[default]
+    Static,
+    // strokes draw themselves one after another.
+    Animated,
+    // a frame per stroke, each adding one more, like textbooks print it.
+    Filmstrip,
+    // drawn with characters right in the terminal, for ssh and machines without a desktop.
+    Terminal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TerminalGlyphs {
+    // 2x4 dots a character, sharpest but needs a font that has them.
+    #[default]
+    Braille,
+    // 1x2 blocks a character, blockier but drawn by about every font.
+    HalfBlock,
+}
+
+// named svg colors dark enough to stand out on white, grey spellings of the same colors left out.
+const CLASSIC: [&str; 41] = [
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dodgerblue",
+    "firebrick",
+    "forestgreen",
+    "fuchsia",
+    "goldenrod",
+    "gray",
+    "green",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "lightcoral",
+    "lightseagreen",
+    "lightslategray",
+    "limegreen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "navy",
+];
+
+// Okabe-Ito, told apart with any kind of color blindness. Its yellow is left out, it vanishes on white.
+const COLORBLIND_SAFE: [&str; 7] = [
+    "#E69F00", "#56B4E9", "#009E73", "#0072B2", "#D55E00", "#CC79A7", "#000000",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub enum Palette {
+    #[default]
+    Classic,
+    ColorblindSafe,
+    // every stroke black, for printing.
+    Monochrome,
+    // any svg colors: ["red", "#0072B2"].
+    Custom(Vec<String>),
+}
+
+impl Palette {
+    // colors go around once there are more strokes than colors.
+    pub fn color(&self, index: usize) -> &str {
+        let pick = |colors: &[&'static str]| colors[index % colors.len()];
+        match self {
+            Palette::Classic => pick(&CLASSIC),
+            Palette::ColorblindSafe => pick(&COLORBLIND_SAFE),
+            Palette::Monochrome => "black",
+            Palette::Custom(colors) if colors.is_empty() => "black",
+            Palette::Custom(colors) => &colors[index % colors.len()],
+        }
+    }
+}
+
+// what the stroke diagram shows besides the strokes and their numbers.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct RenderOptions {
+    pub view: StrokeView,
+    // width and height in pixels, drawing coordinates stay KanjiVG's 109x109.
+    pub size: u32,
+    pub stroke_width: f64,
+    pub palette: Palette,
+    // any svg color, None leaves it transparent.
+    pub background: Option<String>,
+    pub show_numbers: bool,
+    pub font_size: f64,
+    pub font_family: Option<String>,
+    // puts the kvg:type of every stroke next to its number.
+    pub stroke_type_labels: bool,
+    // a dot where every stroke starts and an arrow the way it goes, numbers move out of their way.
+    pub direction_markers: bool,
+    // draws only this stroke (0 based) in color, the rest in gray. Used by drills, never saved.
+    #[serde(skip)]
+    pub highlight: Option<usize>,
+    // how fast the animated strokes get drawn, in units of the 109 wide canvas per second.
+    pub drawing_speed: f64,
+    // seconds between one animated stroke ending and the next one starting.
+    pub stroke_pause: f64,
+    pub frames_per_row: usize,
+    // frame borders and dashed center lines, like practice paper has.
+    pub grid_lines: bool,
+    pub terminal_glyphs: TerminalGlyphs,
+    // how many characters wide the terminal drawing is, narrower terminals get a smaller one.
+    pub terminal_columns: usize,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            view: StrokeView::default(),
+            size: CANVAS_SIZE as u32,
+            stroke_width: 2.0,
+            palette: Palette::default(),
+            background: None,
+            show_numbers: true,
+            font_size: 5.0,
+            font_family: None,
+            stroke_type_labels: false,
+            direction_markers: false,
+            highlight: None,
+            drawing_speed: 60.0,
+            stroke_pause: 0.3,
+            frames_per_row: 5,
+            grid_lines: true,
+            terminal_glyphs: TerminalGlyphs::default(),
+            terminal_columns: 40,
+        }
+    }
+}
+
+// KanjiVG draws everything on this square.
+pub const CANVAS_SIZE: f64 = 109.0;
+
+impl RenderOptions {
+    // svg element for a drawing of width x height KanjiVG units, scaled by size, with the background.
+    pub fn svg_header(&self, width: f64, height: f64) -> String {
+        let scale = f64::from(self.size) / CANVAS_SIZE;
+        let mut header = format!(
+            r#"<svg width="{}" height="{}" viewBox="0 0 {} {}" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" xml:space="preserve" version="1.1"  baseProfile="full">"#,
+            width * scale,
+            height * scale,
+            width,
+            height
+        );
+        if let Some(background) = &self.background {
+            header += &format!(
+                r#"
+<rect width="100%" height="100%" style="fill:{}"/>"#,
+                background
+            );
+        }
+        header
+    }
+
+    // font-size and font-family attributes for the stroke numbers.
+    pub fn font(&self, size: f64) -> String {
+        match &self.font_family {
+            Some(family) => format!(r#"font-size="{}" font-family="{}""#, size, family),
+            None => format!(r#"font-size="{}""#, size),
+        }
+    }
+}
+
+#[cfg(test)]
+mod render_options_test {
+    use super::*;
+
+    #[test]
+    fn palettes_cycle() {
+        let classic = Palette::Classic;
+        assert_eq!(classic.color(0), "darkmagenta");
+        assert_eq!(classic.color(CLASSIC.len()), "darkmagenta");
+        assert!(!CLASSIC.contains(&"floralwhite"));
+        assert_eq!(Palette::ColorblindSafe.color(7), "#E69F00");
+        assert_eq!(Palette::Monochrome.color(100), "black");
+        assert_eq!(Palette::Custom(Vec::new()).color(3), "black");
+        let custom = Palette::Custom(vec!["red".to_string(), "blue".to_string()]);
+        assert_eq!(custom.color(3), "blue");
+
+        let options = RenderOptions {
+            size: 218,
+            background: Some("white".to_string()),
+            font_family: Some("serif".to_string()),
+            ..RenderOptions::default()
+        };
+        assert!(options
+            .svg_header(109.0, 109.0)
+            .starts_with(r#"<svg width="218" height="218" viewBox="0 0 109 109""#));
+        assert!(options
+            .svg_header(109.0, 109.0)
+            .ends_with(r#"style="fill:white"/>"#));
+        assert_eq!(options.font(5.0), r#"font-size="5" font-family="serif""#);
+    }
+}
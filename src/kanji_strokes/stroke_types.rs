@@ -0,0 +1,83 @@
+// CJK strokes KanjiVG marks every path with, code is the usual pinyin abbreviation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrokeType {
+    pub glyph: char,
+    pub code: &'static str,
+    pub name: &'static str,
+}
+
+const STROKE_TYPES: [(char, &str, &str); 36] = [
+    ('㇀', "T", "rising, ends with hane"),
+    ('㇁', "WG", "bent, ends with hane"),
+    ('㇂', "XG", "slanted curve, ends with hane"),
+    ('㇃', "BXG", "flat slanted curve, ends with hane"),
+    ('㇄', "SW", "vertical then bent right"),
+    ('㇅', "HZZ", "horizontal, turn, turn"),
+    ('㇆', "HZG", "horizontal, turn down, ends with hane"),
+    ('㇇', "HP", "horizontal, then sweeps left with harai"),
+    ('㇈', "HZWG", "horizontal, turn, bend, ends with hane"),
+    ('㇉', "SZWG", "vertical, turn, bend, ends with hane"),
+    ('㇊', "HZT", "horizontal, turn, rising"),
+    (
+        '㇋',
+        "HZZP",
+        "horizontal, turn, turn, sweeps left with harai",
+    ),
+    ('㇌', "HPWG", "horizontal, sweep, bend, ends with hane"),
+    ('㇍', "HZW", "horizontal, turn, bend"),
+    ('㇎', "HZZZ", "horizontal with three turns"),
+    ('㇏', "N", "sweeps down right with harai"),
+    ('㇐', "H", "horizontal, ends with tome"),
+    ('㇑', "S", "vertical, ends with tome"),
+    ('㇒', "P", "sweeps down left with harai"),
+    ('㇓', "SP", "vertical, then sweeps left with harai"),
+    ('㇔', "D", "dot"),
+    ('㇕', "HZ", "horizontal, turn down"),
+    ('㇖', "HG", "horizontal, ends with hane"),
+    ('㇗', "SZ", "vertical, turn right"),
+    ('㇘', "SWZ", "vertical, bend, turn"),
+    ('㇙', "ST", "vertical, then rising"),
+    ('㇚', "SG", "vertical, ends with hane"),
+    ('㇛', "PD", "sweep left, then dot"),
+    ('㇜', "PZ", "sweep left, then turn"),
+    ('㇝', "TN", "flat sweep right with harai"),
+    ('㇞', "SZZ", "vertical, turn, turn"),
+    ('㇟', "SWG", "vertical, bend right, ends with hane"),
+    ('㇠', "HXWG", "horizontal, slanted bend, ends with hane"),
+    ('㇡', "HZZZG", "horizontal, three turns, ends with hane"),
+    ('㇢', "PG", "sweep left, ends with hane"),
+    ('㇣', "Q", "circle"),
+];
+
+pub fn all_stroke_types() -> impl Iterator<Item = StrokeType> {
+    STROKE_TYPES
+        .iter()
+        .map(|&(glyph, code, name)| StrokeType { glyph, code, name })
+}
+
+// kvg:type is sometimes a variant '㇐a' or an alternative '㇔/㇀', the first stroke is the main one.
+pub fn stroke_type(kvg_type: &str) -> Option<StrokeType> {
+    let glyph = kvg_type.chars().next()?;
+    all_stroke_types().find(|t| t.glyph == glyph)
+}
+
+impl StrokeType {
+    pub fn pretty_print(&self) -> String {
+        format!("{} {} - {}", self.glyph, self.code, self.name)
+    }
+}
+
+#[cfg(test)]
+mod stroke_types_test {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        assert_eq!(stroke_type("㇒").unwrap().code, "P");
+        assert_eq!(stroke_type("㇐b").unwrap().code, "H");
+        assert_eq!(stroke_type("㇔/㇀").unwrap().code, "D");
+        assert!(stroke_type("").is_none());
+        assert!(stroke_type("x").is_none());
+        assert_eq!(all_stroke_types().count(), 36);
+    }
+}
@@ -0,0 +1,256 @@
+use super::path_data::{bounding_box, Point};
+use super::{KanjiDrawRecipe, Palette, RenderOptions, TerminalGlyphs, CANVAS_SIZE};
+use console::Style;
+
+// 256 color stand-ins for the svg palettes, readable on dark and light terminals alike.
+const CLASSIC_TERMINAL: [u8; 10] = [160, 28, 26, 172, 127, 30, 202, 91, 64, 32];
+// Okabe-Ito again, its black as gray so it shows up on dark terminals.
+const COLORBLIND_SAFE_TERMINAL: [u8; 7] = [214, 74, 36, 25, 166, 175, 244];
+const DIMMED: u8 = 244;
+
+// None is the terminal's own text color.
+fn terminal_color(options: &RenderOptions, index: usize) -> Option<u8> {
+    match options.highlight {
+        Some(highlighted) if highlighted == index => None,
+        Some(_) => Some(DIMMED),
+        None => match options.palette {
+            Palette::Monochrome => None,
+            Palette::ColorblindSafe => {
+                Some(COLORBLIND_SAFE_TERMINAL[index % COLORBLIND_SAFE_TERMINAL.len()])
+            }
+            // svg color names dont carry over, custom palettes get the classic terminal colors.
+            Palette::Classic | Palette::Custom(_) => {
+                Some(CLASSIC_TERMINAL[index % CLASSIC_TERMINAL.len()])
+            }
+        },
+    }
+}
+
+fn foreground(color: Option<u8>) -> Style {
+    match color {
+        Some(color) => Style::new().color256(color),
+        None => Style::new(),
+    }
+}
+
+// braille dot bits, indexed by [y][x] inside the 2x4 cell.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+// every pixel remembers the last stroke drawn over it.
+struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<Option<usize>>,
+}
+
+impl Raster {
+    fn get(&self, x: usize, y: usize) -> Option<usize> {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x]
+        } else {
+            None
+        }
+    }
+}
+
+impl KanjiDrawRecipe {
+    // the strokes as lines of braille or half-block characters, colored per stroke and numbered.
+    // rows above and below the kanji are left out.
+    pub fn generate_terminal_strokes(
+        &self,
+        options: &RenderOptions,
+        columns: usize,
+    ) -> Result<String, String> {
+        let columns = columns.max(2);
+        let rows = columns.div_ceil(2);
+        let (cell_width, cell_height) = match options.terminal_glyphs {
+            TerminalGlyphs::Braille => (2, 4),
+            TerminalGlyphs::HalfBlock => (1, 2),
+        };
+        // pixels are square since a character is about twice as tall as wide.
+        let mut raster = Raster {
+            width: columns * cell_width,
+            height: rows * cell_height,
+            pixels: vec![None; columns * cell_width * rows * cell_height],
+        };
+        let scale = raster.width as f64 / CANVAS_SIZE;
+        let to_pixel = |p: Point| {
+            let x = (p.x * scale).floor();
+            let y = (p.y * scale).floor();
+            if x < 0.0 || y < 0.0 {
+                None
+            } else {
+                Some((x as usize, y as usize))
+            }
+        };
+
+        let mut corners = Vec::new();
+        for (index, p) in self.paths().iter().enumerate() {
+            for point in p.sample(0.5 / scale)? {
+                if let Some((x, y)) = to_pixel(point) {
+                    if x < raster.width && y < raster.height {
+                        raster.pixels[y * raster.width + x] = Some(index);
+                    }
+                }
+            }
+            let (min, max) = p.bounds()?;
+            corners.push(min);
+            corners.push(max);
+        }
+        let mut first_row = rows;
+        let mut last_row = 0;
+        if let Some((min, max)) = bounding_box(&corners) {
+            let row_of = |p: Point| to_pixel(p).map_or(0, |(_, y)| y / cell_height);
+            first_row = row_of(min).min(rows - 1);
+            last_row = row_of(max).min(rows - 1);
+        }
+
+        let mut cells: Vec<Vec<String>> = Vec::new();
+        for row in 0..rows {
+            let mut line = Vec::new();
+            for column in 0..columns {
+                let x = column * cell_width;
+                let y = row * cell_height;
+                let cell = match options.terminal_glyphs {
+                    TerminalGlyphs::Braille => {
+                        let mut bits = 0;
+                        let mut last = None;
+                        for (dy, dots) in BRAILLE_DOTS.iter().enumerate() {
+                            for (dx, dot) in dots.iter().enumerate() {
+                                if let Some(index) = raster.get(x + dx, y + dy) {
+                                    bits |= dot;
+                                    last = last.max(Some(index));
+                                }
+                            }
+                        }
+                        match last {
+                            Some(index) => foreground(terminal_color(options, index))
+                                .apply_to(std::char::from_u32(0x2800 + bits).unwrap())
+                                .to_string(),
+                            None => " ".to_string(),
+                        }
+                    }
+                    TerminalGlyphs::HalfBlock => {
+                        match (raster.get(x, y), raster.get(x, y + 1)) {
+                            (None, None) => " ".to_string(),
+                            (Some(top), None) => foreground(terminal_color(options, top))
+                                .apply_to('▀')
+                                .to_string(),
+                            (None, Some(bottom)) => foreground(terminal_color(options, bottom))
+                                .apply_to('▄')
+                                .to_string(),
+                            (Some(top), Some(bottom)) => {
+                                let style = foreground(terminal_color(options, top));
+                                match terminal_color(options, bottom) {
+                                    // two strokes meet, the lower one paints the background.
+                                    Some(color) if top != bottom => {
+                                        style.on_color256(color).apply_to('▀').to_string()
+                                    }
+                                    _ => style.apply_to('█').to_string(),
+                                }
+                            }
+                        }
+                    }
+                };
+                line.push(cell);
+            }
+            cells.push(line);
+        }
+
+        if options.show_numbers {
+            let mut labeled = vec![vec![false; columns]; rows];
+            for (index, p) in self.paths().iter().enumerate() {
+                let (x, y) = p.extract_x_y()?;
+                let (x, y) = match to_pixel(Point::new(x, y)) {
+                    Some((x, y)) => (x / cell_width, y / cell_height),
+                    None => (0, 0),
+                };
+                let number = (index + 1).to_string();
+                let length = number.len();
+                // just left of where the stroke starts, else above, below or right of it.
+                let candidates = [
+                    (y as isize, x as isize - length as isize),
+                    (y as isize - 1, x as isize),
+                    (y as isize + 1, x as isize),
+                    (y as isize, x as isize + 1),
+                ];
+                let fits = |&(row, column): &(isize, isize)| {
+                    row >= 0
+                        && column >= 0
+                        && (row as usize) < rows
+                        && column as usize + length <= columns
+                        && !labeled[row as usize][column as usize..column as usize + length]
+                            .contains(&true)
+                };
+                let (row, column) = match candidates.iter().find(|c| fits(c)) {
+                    Some(&(row, column)) => (row as usize, column as usize),
+                    None => (y.min(rows - 1), x.min(columns.saturating_sub(length))),
+                };
+                let style = foreground(terminal_color(options, index)).bold();
+                // too narrow for the whole number, its leading digits have to do.
+                for (offset, digit) in number.chars().enumerate().take(columns - column) {
+                    cells[row][column + offset] = style.apply_to(digit).to_string();
+                    labeled[row][column + offset] = true;
+                }
+                first_row = first_row.min(row);
+                last_row = last_row.max(row);
+            }
+        }
+
+        if first_row > last_row {
+            return Ok(String::new());
+        }
+        Ok(cells[first_row..=last_row]
+            .iter()
+            .map(|line| line.concat().trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod terminal_strokes_test {
+    use super::*;
+    use crate::kanji_strokes::{test_recipe, test_two};
+
+    #[test]
+    fn draws_with_characters() {
+        let two = test_two();
+
+        let half_blocks = RenderOptions {
+            terminal_glyphs: TerminalGlyphs::HalfBlock,
+            ..RenderOptions::default()
+        };
+        let drawn = two.generate_terminal_strokes(&half_blocks, 20).unwrap();
+        let plain = console::strip_ansi_codes(&drawn);
+        let lines: Vec<&str> = plain.lines().collect();
+        // 20 pixels across: y=30 is pixel 5, row 2, y=80 is pixel 14, row 7.
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "    1▄▄▄▄▄▄▄▄▄▄");
+        assert_eq!(lines[5], "2▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀");
+
+        let braille = RenderOptions {
+            show_numbers: false,
+            ..RenderOptions::default()
+        };
+        let drawn = two.generate_terminal_strokes(&braille, 20).unwrap();
+        let plain = console::strip_ansi_codes(&drawn);
+        assert!(!plain.contains('1'));
+        assert!(plain
+            .chars()
+            .all(|c| c == ' ' || c == '\n' || ('⠀'..='⣿').contains(&c)));
+        assert_eq!(plain.lines().count(), 6);
+    }
+
+    #[test]
+    fn numbers_wider_than_the_drawing() {
+        let strokes: Vec<String> = (0..100).map(|i| format!("M{},50 l1,0", i)).collect();
+        let strokes: Vec<&str> = strokes.iter().map(|s| s.as_str()).collect();
+        let many = test_recipe('鬱', &strokes);
+
+        let drawn = many
+            .generate_terminal_strokes(&RenderOptions::default(), 2)
+            .unwrap();
+        assert!(console::strip_ansi_codes(&drawn).contains("10"));
+    }
+}
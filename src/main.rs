@@ -1,200 +1,58 @@
-use std::collections::BTreeMap;
-use std::path::Path;
-
-use serde::{Deserialize, Serialize};
-
-use crate::kanji_strokes::KanjiDrawRecipe;
-use bincode::deserialize;
+use crate::book::{Book, Entry, QuizMode};
+use crate::database::{Database, Kanji};
+use crate::kanji_strokes::{KanjiDrawRecipe, Palette, RenderOptions, StrokeType, StrokeView};
+use crate::review_log::ReviewLog;
+use crate::scheduler::{Grade, SchedulerKind};
+use crate::settings::Settings;
 use console::Term;
-use rand::Rng;
-use std::io::{BufReader, Write};
+use rand::seq::SliceRandom;
+use std::collections::BTreeSet;
+use std::io::Write;
 
+mod book;
+mod database;
+mod kana;
 mod kanji_dict;
 mod kanji_strokes;
+mod review_log;
+mod scheduler;
+mod settings;
 
-#[derive(Serialize, Deserialize, Clone)]
-struct Kanji {
-    on_readings: Vec<String>,
-    kun_readings: Vec<String>,
-    meaning: Vec<String>,
-    literal: char,
-}
-
-impl Kanji {
-    fn pretty_print(&self) -> String {
-        // maybe as display one day.
-        format!(
-            "kanji: {}, meanings: {:?}, on_readings: {:?}, kun_readings: {:?}",
-            self.literal, self.meaning, self.on_readings, self.kun_readings
-        )
-    }
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct Entry {
-    kanji: char,
-    confidence_level: i32, // 0 - 5?
-}
-
-#[derive(Debug)]
-struct Book {
-    kanjis: BTreeMap<char, Entry>,
-}
-
-impl Book {
-    pub fn new(kanjis: BTreeMap<char, Entry>) -> Self {
-        Book { kanjis }
-    }
-
-    pub fn roll(&self) -> Kanji {
-        // let mut rng = rand::thread_rng();
-        // let v= Rng::gen_range(&mut rng, 0..self.previous_sum);
-        //
-        // let mut stopper = 0;
-        // for (_, k) in self.kanjis.iter() {
-        //     stopper += k.confidence_level.val();
-        //
-        //     if stopper >= v {
-        //         return k.clone();
-        //     }
-        // }
-
-        panic!("should always pick out a random kanji. not really, go from lowest confidenc elevel and there pick now")
-    }
-
-    pub fn add(&mut self, entry: Entry) {
-        self.kanjis.insert(entry.kanji, entry);
-    }
-    pub fn save(&self, file_name: &str) {
-        let serialized = serde_json::to_string(&self.kanjis).expect("Unable to serialize book!");
-        let mut f = std::fs::OpenOptions::new()
-            .write(true)
-            .create(true)
-            .open(file_name)
-            .expect("Couldnt open file for writing.");
-
-        f.write_all(serialized.as_bytes());
-    }
-
-    pub fn add_save(&mut self, entry: Entry, file_name: &str) {
-        self.add(entry);
-        self.save(file_name);
-    }
-}
-
-fn convert_parsed_to_kanji_vec(kanji_dictionary: &kanji_dict::KanjiDictionary) -> Vec<Kanji> {
-    let mut kanji_vec = Vec::new();
-    for c in &kanji_dictionary.character {
-        let mut reading_on = Vec::new();
-        let mut reading_kun = Vec::new();
-        let mut meaning = Vec::new();
-        if let Some(reading_meaning) = c.reading_meaning.clone() {
-            let reading = reading_meaning.rmgroup.reading.unwrap_or(vec![]);
-            for r in reading {
-                if r.r_type == "ja_on" {
-                    reading_on.push(r.value)
-                } else if r.r_type == "ja_kun" {
-                    reading_kun.push(r.value)
-                }
-            }
-
-            let meaning_dict = reading_meaning.rmgroup.meaning.unwrap_or(vec![]);
-            for m in meaning_dict {
-                if m.m_lang == "en" {
-                    meaning.push(m.value);
-                }
-            }
-        }
-
-        kanji_vec.push(Kanji {
-            meaning,
-            literal: c.literal,
-            kun_readings: reading_kun,
-            on_readings: reading_on,
-        });
-    }
-
-    kanji_vec
-}
-
-fn dump_kanjis(lookup_dict: &Vec<Kanji>) {
-    let v = serde_json::to_string(&lookup_dict).expect("unable to ser");
-    let mut f = std::fs::OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open("kanji.json")
-        .expect("Couldnt open file showcase.svg for writing.");
-
-    f.write(v.as_bytes());
-}
-
-fn dump_db(db: &Database) {
-    let mut f = std::fs::OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open("db.bin")
-        .expect("Couldnt open file showcase.svg for writing.");
-    bincode::serialize_into(f, db);
-}
-
-fn load_db_from_plain_file() -> Database {
-    let f = std::fs::OpenOptions::new()
-        .read(true)
-        .open("db.bin")
-        .expect("couldnt load kanji.json for reading");
-    let reader = BufReader::new(f);
-    let db = bincode::deserialize_from(reader).expect("couldnt decode kanji vec");
-
-    db
-}
-
-fn parse_dict() -> kanji_dict::KanjiDictionary {
-    serde_xml_rs::from_reader(std::fs::File::open("kanjidic2.xml").expect("Couldnt open dict file"))
-        .expect("Couldnt load dict!")
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-struct Database {
-    kanjis: Vec<Kanji>,
-    strokes: BTreeMap<char, kanji_strokes::KanjiDrawRecipe>,
-}
-
-impl Database {
-    fn find(&self, pattern: &str) -> Vec<(Kanji, Option<KanjiDrawRecipe>)> {
-        let mut matching_kanjis = Vec::new();
-
-        for k in &self.kanjis {
-            if pattern.contains(k.literal) || k.meaning.iter().any(|m| m == pattern) {
-                matching_kanjis.push((k.clone(), self.strokes.get(&k.literal).cloned()));
-            }
-        }
-
-        matching_kanjis
-    }
-}
+const MAX_SHOWN_RESULTS: usize = 30;
 
 fn ask_user_to_select_one_from_result(
     term: &console::Term,
     pattern: &str,
     results: &[(Kanji, Option<KanjiDrawRecipe>)],
+    settings: &Settings,
 ) -> Option<(Kanji, Option<KanjiDrawRecipe>)> {
     if results.is_empty() {
         term.write_line(&format!(
             "Your pattern: '{}' has not matched any of the kanjis in db.",
             pattern
-        ));
+        ))
+        .unwrap();
+        return None;
     }
 
     if results.len() == 1 {
         return Some(results[0].clone());
     }
 
-    term.write_line("Matched kanjis:");
+    // results come ranked, the best ones are on top.
+    let results = &results[..results.len().min(MAX_SHOWN_RESULTS)];
+    term.write_line("Matched kanjis:").unwrap();
     for (i, k) in results.iter().enumerate() {
-        term.write_line(&format!("{}: {}", i, k.0.pretty_print()));
+        term.write_line(&format!(
+            "{}: {}",
+            i,
+            k.0.pretty_print(&settings.languages, settings.show_foreign_readings)
+        ))
+        .unwrap();
     }
 
-    term.write_line("Has any of those matched your query? pick the number");
+    term.write_line("Has any of those matched your query? pick the number")
+        .unwrap();
 
     let number: usize = term
         .read_line()
@@ -209,61 +67,417 @@ fn ask_user_to_select_one_from_result(
             number,
             0,
             results.len() - 1
-        ));
+        ))
+        .unwrap();
+    }
+
+    None
+}
+
+// component with the meanings of its element, when it is a kanji of its own.
+fn describe_component(db: &Database, settings: &Settings, c: &kanji_strokes::Component) -> String {
+    let meaning = c
+        .element
+        .as_ref()
+        .and_then(|e| e.chars().next())
+        .and_then(|e| db.kanji(e))
+        .map(|k| format!(" {:?}", k.meaning(&settings.languages)))
+        .unwrap_or_default();
+    c.describe() + &meaning
+}
+
+fn select_and_offer_adding(
+    term: &Term,
+    book: &mut Book,
+    file_name: &str,
+    settings: &Settings,
+    pattern: &str,
+    results: &[(Kanji, Option<KanjiDrawRecipe>)],
+) {
+    if let Some(single_result) =
+        ask_user_to_select_one_from_result(term, pattern, results, settings)
+    {
+        term.write_line(&format!(
+            "You have selected: {}",
+            single_result
+                .0
+                .pretty_print(&settings.languages, settings.show_foreign_readings)
+        ))
+        .unwrap();
+
+        term.write_line("Do you wish to add it to your knowledge base? [y/N]")
+            .unwrap();
+        if term.read_char().unwrap().eq_ignore_ascii_case(&'y') {
+            book.add_save(Entry::new(single_result.0.literal), file_name);
+            term.write_line(&format!("Added {} to your base", single_result.0.literal))
+                .unwrap();
+        } else {
+            term.write_line("Skipping addition.").unwrap();
+        }
+    }
+}
+
+// shows meanings of the kanji and lets the user grade themselves, None if they want to stop.
+fn ask_meaning(term: &Term, db: &Database, kanji: char, settings: &Settings) -> Option<Grade> {
+    let k = db.get(kanji).expect("Has to have this entry.");
+
+    term.write_line(&format!(
+        "Kanji that has meanings: {:?}",
+        k.0.meaning(&settings.languages)
+    ))
+    .unwrap();
+
+    loop {
+        term.write_line("[1/space] - you know it [2] - not confident [3] - fail [s] - see strokes [x] - stop quizzing")
+            .unwrap();
+        match term.read_char().expect("char!").to_ascii_lowercase() {
+            '1' | ' ' => return Some(Grade::Good),
+            '2' => return Some(Grade::Hard),
+            '3' => return Some(Grade::Again),
+            's' => {
+                show_strokes(term, kanji, &k.1, &settings.render);
+            }
+            'x' => return None,
+            _ => {
+                // noop
+            }
+        }
     }
+}
+
+// quiz modes where the answer gets typed in, not every kanji can be asked in every mode.
+fn can_be_typed_quiz(k: &Kanji, mode: QuizMode) -> bool {
+    match mode {
+        QuizMode::Meaning => false,
+        QuizMode::Reading => !k.on_readings.is_empty() || !k.kun_readings.is_empty(),
+        QuizMode::Nanori => !k.nanori.is_empty(),
+        QuizMode::Radical => k.radical.and_then(database::radical).is_some(),
+    }
+}
+
+fn typed_quiz(
+    term: &Term,
+    db: &Database,
+    book: &mut Book,
+    log: &ReviewLog,
+    settings: &Settings,
+    file_name: &str,
+    mode: QuizMode,
+) {
+    loop {
+        let quiz = match book.roll(mode, scheduler::now(), |k| {
+            db.kanji(k)
+                .is_some_and(|k| settings.quiz_filter.matches(k) && can_be_typed_quiz(k, mode))
+        }) {
+            Some(quiz) => quiz,
+            None => {
+                term.write_line("Nothing is due for a review right now, come back later. Press any key to continue.")
+                    .unwrap();
+                term.read_char().unwrap();
+                return;
+            }
+        };
+
+        let k = db.kanji(quiz).expect("Has to have this entry.");
+        let (question, correct_answers) = match mode {
+            QuizMode::Reading => (
+                "a on or kun reading",
+                format!(
+                    "on_readings: {:?}, kun_readings: {:?}",
+                    k.on_readings, k.kun_readings
+                ),
+            ),
+            QuizMode::Nanori => ("a name reading", format!("nanori: {:?}", k.nanori)),
+            QuizMode::Radical => (
+                "the radical",
+                format!(
+                    "radical: {}",
+                    k.radical
+                        .and_then(database::radical)
+                        .expect("only kanjis with a known radical get asked")
+                        .pretty_print()
+                ),
+            ),
+            QuizMode::Meaning => unreachable!("meaning quiz is not typed in"),
+        };
 
-    return None;
+        term.clear_screen().unwrap();
+        let hint = match mode {
+            QuizMode::Radical => "number, glyph or name",
+            _ => "romaji gets converted, hiragana and katakana both count",
+        };
+        term.write_line(&format!(
+            "Type in {} of: {} ({}, leave empty if you dont know)",
+            question, k.literal, hint
+        ))
+        .unwrap();
+        let asked_at = std::time::Instant::now();
+        let answer = match mode {
+            QuizMode::Radical => {
+                term.write_str("> ").unwrap();
+                term.read_line().unwrap()
+            }
+            _ => kana::read_kana_line(term, "> ").unwrap(),
+        };
+        let response_ms = asked_at.elapsed().as_millis() as u64;
+
+        let correct = match mode {
+            QuizMode::Nanori => k.matches_nanori(&answer),
+            QuizMode::Radical => {
+                database::find_radical(&answer).is_some_and(|r| k.radical == Some(r.number))
+            }
+            _ => k.matches_reading(&answer),
+        };
+        let grade = if correct {
+            term.write_line("Correct!").unwrap();
+            Grade::Good
+        } else {
+            term.write_line("Wrong.").unwrap();
+            Grade::Again
+        };
+        term.write_line(&correct_answers).unwrap();
+
+        let record = book.grade(quiz, mode, grade, scheduler::now(), response_ms);
+        log.append(&record);
+        book.save(file_name);
+
+        term.write_line("Press any key to continue, [x] to stop quizzing.")
+            .unwrap();
+        if term.read_char().unwrap().eq_ignore_ascii_case(&'x') {
+            return;
+        }
+    }
 }
 
-fn show_strokes(term: &Term, literal: char, strokes: &Option<KanjiDrawRecipe>) {
+fn show_strokes(
+    term: &Term,
+    literal: char,
+    strokes: &Option<KanjiDrawRecipe>,
+    options: &RenderOptions,
+) {
     if let Some(strokes) = strokes {
-        let body = strokes.generate_svg();
-        let mut f = std::fs::OpenOptions::new()
-            .truncate(true)
-            .write(true)
-            .create(true)
-            .open("showcase.svg")
-            .expect("Couldnt open file showcase.svg for writing.");
-
-        write!(f, "{}", body);
-        open::that("showcase.svg");
+        show_recipe(term, strokes, options);
     } else {
         term.write_line(&format!(
             "Kanji has been recognized but it seems we dont have strokes for it: {}",
             literal
-        ));
+        ))
+        .unwrap();
     };
 }
 
-fn main() {
-    let file_name = "dict.json";
-    let mut entries: BTreeMap<char, Entry> = if Path::new(file_name).exists() {
-        serde_json::from_reader(std::fs::File::open(file_name).expect("Couldnt open file"))
-            .expect(&format!("Unable to parse out dict from {}", file_name))
-    } else {
-        BTreeMap::new()
+// svg files for the desktop to open, drawn in the terminal when there is nothing to open them with.
+fn show_recipe(term: &Term, recipe: &KanjiDrawRecipe, options: &RenderOptions) {
+    let (file_name, svg) = match options.view {
+        StrokeView::Static => ("showcase.svg", recipe.generate_svg(options)),
+        StrokeView::Animated => ("animated.svg", recipe.generate_animated_svg(options)),
+        StrokeView::Filmstrip => ("filmstrip.svg", recipe.generate_filmstrip_svg(options)),
+        StrokeView::Terminal => return show_in_terminal(term, recipe, options),
+    };
+    if !gui_available() || !show_svg(term, file_name, svg) {
+        show_in_terminal(term, recipe, options);
+    }
+}
+
+// xdg-open happily succeeds without a display to show anything on, so look before opening.
+fn gui_available() -> bool {
+    let over_ssh =
+        std::env::var_os("SSH_CONNECTION").is_some() || std::env::var_os("SSH_TTY").is_some();
+    let has_display = cfg!(any(target_os = "macos", target_os = "windows"))
+        || std::env::var_os("DISPLAY").is_some()
+        || std::env::var_os("WAYLAND_DISPLAY").is_some();
+    !over_ssh && has_display
+}
+
+fn show_in_terminal(term: &Term, recipe: &KanjiDrawRecipe, options: &RenderOptions) {
+    let (_, width) = term.size();
+    let columns = options.terminal_columns.min(usize::from(width));
+    match recipe.generate_terminal_strokes(options, columns) {
+        Ok(drawing) => term.write_line(&drawing).unwrap(),
+        Err(e) => term
+            .write_line(&format!("Couldnt draw the strokes: {}", e))
+            .unwrap(),
+    }
+}
+
+// false when nothing got shown, the svg couldnt be drawn or opened.
+fn show_svg(term: &Term, file_name: &str, svg: Result<String, String>) -> bool {
+    let body = match svg {
+        Ok(body) => body,
+        Err(e) => {
+            term.write_line(&format!("Couldnt draw the strokes: {}", e))
+                .unwrap();
+            return false;
+        }
     };
+    let mut f = std::fs::OpenOptions::new()
+        .truncate(true)
+        .write(true)
+        .create(true)
+        .open(file_name)
+        .unwrap_or_else(|e| panic!("Couldnt open file {} for writing: {}", file_name, e));
+
+    write!(f, "{}", body).unwrap_or_else(|e| panic!("Couldnt write {}: {}", file_name, e));
+    open::that(file_name).is_ok()
+}
+
+const STROKE_TYPE_CHOICES: usize = 4;
+
+// highlights a random stroke of a random known kanji and asks which type it is, not scheduled.
+fn stroke_type_drill(term: &Term, db: &Database, book: &Book, render: &RenderOptions) {
+    let mut rng = rand::thread_rng();
+    let drillable: Vec<(char, &KanjiDrawRecipe)> = book
+        .kanjis
+        .keys()
+        .filter_map(|k| db.strokes.get(k).map(|s| (*k, s)))
+        .filter(|(_, s)| s.paths().iter().any(|p| p.stroke_type.is_some()))
+        .collect();
 
-    if !std::path::Path::new("db.bin").exists() {
-        let kanjivg = kanji_strokes::parse_kanjivg();
-        let strokes = kanji_strokes::kanjivg_into_strokes(&kanjivg);
-        let dict = parse_dict();
+    loop {
+        let (literal, recipe) = match drillable.choose(&mut rng) {
+            Some(drilled) => *drilled,
+            None => {
+                term.write_line("None of your kanjis has stroke types. Press any key to continue.")
+                    .unwrap();
+                term.read_char().unwrap();
+                return;
+            }
+        };
+        let typed: Vec<(usize, StrokeType)> = recipe
+            .paths()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| Some((i, kanji_strokes::stroke_type(p.stroke_type.as_ref()?)?)))
+            .collect();
+        let (index, correct) = match typed.choose(&mut rng) {
+            Some(stroke) => *stroke,
+            None => continue,
+        };
 
-        let parsed = convert_parsed_to_kanji_vec(&dict);
-        let db = Database {
-            strokes: strokes.dict,
-            kanjis: parsed,
+        // the highlighted stroke needs a still picture.
+        let view = match render.view {
+            StrokeView::Terminal => StrokeView::Terminal,
+            _ => StrokeView::Static,
         };
+        show_recipe(
+            term,
+            recipe,
+            &RenderOptions {
+                view,
+                highlight: Some(index),
+                // the label would give the answer away.
+                stroke_type_labels: false,
+                ..render.clone()
+            },
+        );
+
+        let mut choices: Vec<StrokeType> = kanji_strokes::all_stroke_types()
+            .filter(|t| *t != correct)
+            .collect::<Vec<_>>()
+            .choose_multiple(&mut rng, STROKE_TYPE_CHOICES - 1)
+            .copied()
+            .collect();
+        choices.push(correct);
+        choices.shuffle(&mut rng);
+
+        term.clear_screen().unwrap();
+        term.write_line(&format!(
+            "What type is the black stroke {} of {}?",
+            index + 1,
+            literal
+        ))
+        .unwrap();
+        for (i, choice) in choices.iter().enumerate() {
+            term.write_line(&format!("[{}] {}", i + 1, choice.pretty_print()))
+                .unwrap();
+        }
+
+        let picked = term
+            .read_char()
+            .unwrap()
+            .to_digit(10)
+            .and_then(|d| choices.get((d as usize).checked_sub(1)?));
+        if picked == Some(&correct) {
+            term.write_line("Correct!").unwrap();
+        } else {
+            term.write_line(&format!("Wrong, it is {}", correct.pretty_print()))
+                .unwrap();
+        }
+
+        term.write_line("Press any key to continue, [x] to stop drilling.")
+            .unwrap();
+        if term.read_char().unwrap().eq_ignore_ascii_case(&'x') {
+            return;
+        }
+    }
+}
+
+// offline maintenance commands working only on the review log, no terminal ui involved.
+fn run_command(
+    command: &str,
+    book: &mut Book,
+    file_name: &str,
+    log: &ReviewLog,
+    settings: &mut Settings,
+    settings_file_name: &str,
+) {
+    match command {
+        "replay" => {
+            let records = log.load();
+            book.replay(&records);
+            book.save(file_name);
+            println!(
+                "Rebuilt {} from {} logged reviews.",
+                file_name,
+                records.len()
+            );
+        }
+        "refit" => {
+            let histories = review_log::histories(&log.load());
+            match scheduler::fit_parameters(&settings.fsrs().parameters, &histories) {
+                Some(parameters) => {
+                    settings.fsrs_parameters = Some(parameters);
+                    settings.save(settings_file_name);
+                    println!("Refitted FSRS parameters: {:?}", parameters);
+                }
+                None => println!("Not enough reviews logged to refit FSRS parameters."),
+            }
+        }
+        _ => println!(
+            "Unknown command: {}, known ones are: replay, refit",
+            command
+        ),
+    }
+}
+
+fn main() {
+    let file_name = "dict.json";
+    let settings_file_name = "settings.json";
+    let mut settings = Settings::load(settings_file_name);
+    let log = ReviewLog::new("review_log.jsonl");
 
-        dump_db(&db);
+    if let Some(command) = std::env::args().nth(1) {
+        let mut book = Book::load(file_name, settings.make_scheduler());
+        run_command(
+            &command,
+            &mut book,
+            file_name,
+            &log,
+            &mut settings,
+            settings_file_name,
+        );
+        return;
     }
 
-    let db = load_db_from_plain_file();
+    let db = database::load_db_from_plain_file().unwrap_or_else(|| {
+        let db = database::build_db();
+        database::dump_db(&db);
+        db
+    });
 
-    let mut book = Book::new(entries);
+    let mut book = Book::load(file_name, settings.make_scheduler());
 
     let term = console::Term::stdout();
-    let mut rng = rand::thread_rng();
 
     loop {
         term.clear_screen().unwrap();
@@ -274,129 +488,554 @@ fn main() {
         //     term.read_key();
         // }
 
-        term.write_line("Poll[y] add[a] add-[f]ull [l]ist anything else exits. [s]troke")
-            .unwrap();
+        term.write_line(&format!(
+            "Poll[y] add[a] add-by-[f]ilter [l]ist anything else exits. [s]troke [c]hange scheduler leitner [b]oxes [r]eading quiz [n]ame reading quiz name readings in searc[h] ({nanori}) [o]rder by textbook lan[g]uages for[e]ign readings [k]anjis by radical rad[i]cal quiz [m]ulti-component lookup [d]ecompose stroke [t]ype drill stroke [v]iew stroke [p]alette direction arro[w]s",
+            nanori = if settings.search.include_nanori { "on" } else { "off" },
+        ))
+        .unwrap();
         match term.read_char().unwrap() {
-            'q' => {
-                loop {
-                    let mut sorted = BTreeMap::new();
-                    for (k, e) in &book.kanjis {
-                        let e = sorted.entry(e.confidence_level).or_insert(Vec::new());
-                        e.push(*k);
+            'q' => loop {
+                let quiz = match book.roll(QuizMode::Meaning, scheduler::now(), |k| {
+                    db.matches_filter(k, &settings.quiz_filter)
+                }) {
+                    Some(quiz) => quiz,
+                    None => {
+                        term.write_line("Nothing is due for a review right now, come back later. Press any key to continue.")
+                                .unwrap();
+                        term.read_char().unwrap();
+                        break;
                     }
+                };
 
-                    let (_, kanjis) = sorted
-                        .iter()
-                        .next()
-                        .expect("tried to quiz when you dont have anything in knowledge base.");
-                    use rand::seq::SliceRandom;
-                    let quiz = kanjis.choose(&mut rng).expect("impossibru.");
-
-                    let find_result = db.find(&quiz.to_string());
-                    let k = find_result.first().expect("Has to have this entry.");
-
-                    term.write_line(&format!("Kanji that has meanings: {:?}", k.0.meaning));
-
-                    loop {
-                        term.write_line("[1/space] - you know it [2] - not confident [3] - fail [s] - see strokes");
-                        match term.read_char().expect("char!").to_ascii_lowercase() {
-                            '1' | ' ' => {
-                                book.kanjis.get_mut(quiz).unwrap().confidence_level += 1;
-                                break;
-                            }
-                            '2' => {
-                                // noop
-                                break;
-                            }
-                            '3' => {
-                                book.kanjis.get_mut(quiz).unwrap().confidence_level = 0;
-                                break;
-                            }
-                            's' => {
-                                show_strokes(&term, *quiz, &k.1);
-                            }
-                            _ => {
-                                // noop
-                            }
-                        }
+                let asked_at = std::time::Instant::now();
+                let grade = match ask_meaning(&term, &db, quiz, &settings) {
+                    Some(grade) => grade,
+                    None => break,
+                };
+
+                let record = book.grade(
+                    quiz,
+                    QuizMode::Meaning,
+                    grade,
+                    scheduler::now(),
+                    asked_at.elapsed().as_millis() as u64,
+                );
+                log.append(&record);
+                book.save(file_name);
+            },
+            'f' => {
+                term.write_line("Add every kanji matching a filter, e.g.: 'n3', 'grade<=4', 'top500', 'strokes=5-10' (KANJIDIC2 knows only old JLPT levels, n3 and n2 are the same):")
+                    .unwrap();
+                let filter = match database::KanjiFilter::parse(&term.read_line().unwrap()) {
+                    Ok(filter) => filter,
+                    Err(e) => {
+                        term.write_line(&format!("{}. Press any key to continue.", e))
+                            .unwrap();
+                        term.read_char().unwrap();
+                        continue;
                     }
+                };
 
+                let matching = db.filter(&filter);
+                let missing = book.missing(&matching);
+                term.write_line(&format!(
+                    "{} kanjis match, {} of them are not in your knowledge base yet. Add them? [y/N]",
+                    matching.len(),
+                    missing.len()
+                ))
+                .unwrap();
+                if term.read_char().unwrap().eq_ignore_ascii_case(&'y') {
+                    let added = book.add_missing(&missing);
                     book.save(file_name);
+                    term.write_line(&format!("Added {} kanjis.", added))
+                        .unwrap();
+                } else {
+                    term.write_line("Skipping addition.").unwrap();
                 }
+
+                term.write_line("Press any key to continue.").unwrap();
+                term.read_char().unwrap();
             }
-            'f' => {
-                // term.write_line("Selected add full, easier faster adding, here is a template:").unwrap();
-                // term.write_line(r#"{"romanjis":["ichi"],"meaning":["one"],"kanji":"一","confidence_level":{"level":0}}"#).unwrap();
-                // let full_entry = term.read_line().unwrap();
-                // let deserialized = serde_json::from_str(&full_entry).expect(&format!("Couldnt deserialize your entry: {}", full_entry));
-                // term.write_line(&format!("add? y/N: {:?}", deserialized)).unwrap();
-                // if term.read_char().unwrap().to_ascii_lowercase() == 'y' {
-                //     book.add(deserialized);
-                //     term.write_line("Added, press any key to continue.").unwrap();
-                //     term.read_key().unwrap();
-                // }
+            'o' => {
+                let types = db.dic_ref_types();
+                term.write_line(&format!(
+                    "Which book should the deck follow? heisig6 is Remembering the Kanji 6th edition. Known ones: {}",
+                    types.iter().cloned().collect::<Vec<_>>().join(", ")
+                ))
+                .unwrap();
+                let dr_type = term.read_line().unwrap().trim().to_string();
+                let order = db.textbook_order(&dr_type);
+                if order.is_empty() {
+                    term.write_line(&format!(
+                        "No kanji is indexed by '{}'. Press any key to continue.",
+                        dr_type
+                    ))
+                    .unwrap();
+                    term.read_char().unwrap();
+                    continue;
+                }
+
+                let missing = book.missing(&order).len();
+                term.write_line(&format!(
+                    "{} kanjis in the book, {} of them are not in your knowledge base yet. How many of the next ones to add? (empty only reorders)",
+                    order.len(),
+                    missing
+                ))
+                .unwrap();
+                let add_up_to = term.read_line().unwrap().trim().parse().unwrap_or(0);
+                let added = book.follow_order(&order, add_up_to);
+                book.save(file_name);
+                term.write_line(&format!(
+                    "Deck follows {} now, added {} kanjis. Press any key to continue.",
+                    dr_type, added
+                ))
+                .unwrap();
+                term.read_char().unwrap();
             }
             'a' => {
-                term.write_line("Gimme Pattern to search the dict by either kanji or meaning: ")
-                    .unwrap();
+                term.write_line(
+                    "Gimme Pattern to search the dict by either kanji, meaning, reading (end it with * to match reading prefix), pinyin like ya4, SKIP code like 1-3-* or four corner code like 1010: ",
+                )
+                .unwrap();
                 let pattern = term.read_line().unwrap();
-                let matching_kanjis = db.find(&pattern);
+                let matching_kanjis = db.find(&pattern, &settings.search, &settings.languages);
 
-                if let Some(single_result) =
-                    ask_user_to_select_one_from_result(&term, &pattern, &matching_kanjis)
-                {
+                select_and_offer_adding(
+                    &term,
+                    &mut book,
+                    file_name,
+                    &settings,
+                    &pattern,
+                    &matching_kanjis,
+                );
+
+                term.write_line("Press return to continue.").unwrap();
+                term.read_line().unwrap();
+            }
+            'm' => {
+                let mut picked = BTreeSet::new();
+                loop {
+                    let candidates = db.find_by_components(&picked);
+                    let possible = db.possible_components(&candidates);
+                    term.clear_screen().unwrap();
                     term.write_line(&format!(
-                        "You have selected: {}",
-                        single_result.0.pretty_print()
-                    ));
-
-                    term.write_line("Do you wish to add it to your knowledge base? [y/N]");
-                    if term.read_char().unwrap().to_ascii_lowercase() == 'y' {
-                        book.add_save(
-                            Entry {
-                                kanji: single_result.0.literal,
-                                confidence_level: 0,
-                            },
-                            file_name,
+                        "Picked components: {} - {} kanjis have them all.",
+                        picked.iter().collect::<String>(),
+                        candidates.len()
+                    ))
+                    .unwrap();
+                    if !picked.is_empty() {
+                        let shown = candidates.len().min(MAX_SHOWN_RESULTS);
+                        term.write_line(&candidates[..shown].iter().collect::<String>())
+                            .unwrap();
+                    }
+                    term.write_line(&format!(
+                        "Still possible: {}",
+                        possible
+                            .difference(&picked)
+                            .map(|c| c.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    ))
+                    .unwrap();
+                    term.write_line("Type in components to add, -component to drop one, empty line to pick one of the kanjis:")
+                        .unwrap();
+
+                    let input = term.read_line().unwrap();
+                    let input = input.trim();
+                    if input.is_empty() {
+                        if picked.is_empty() {
+                            break;
+                        }
+                        let results: Vec<_> =
+                            candidates.iter().filter_map(|k| db.get(*k)).collect();
+                        let pattern = picked.iter().collect::<String>();
+                        select_and_offer_adding(
+                            &term, &mut book, file_name, &settings, &pattern, &results,
                         );
-                        term.write_line(&format!("Added {} to your base", single_result.0.literal));
+                        break;
+                    } else if let Some(dropped) = input.strip_prefix('-') {
+                        for c in dropped.chars() {
+                            picked.remove(&c);
+                        }
+                    } else {
+                        // a component nothing is made of would just empty the candidates,
+                        // every accepted one narrows down what the next can be.
+                        let mut rejected = String::new();
+                        for c in input.chars().filter(|c| !c.is_whitespace()) {
+                            let possible = db.possible_components(&db.find_by_components(&picked));
+                            if !possible.contains(&c) || !picked.insert(c) {
+                                rejected.push(c);
+                            }
+                        }
+                        if !rejected.is_empty() {
+                            term.write_line(&format!(
+                                "Skipped {}, no kanji left has them. Press any key to continue.",
+                                rejected
+                            ))
+                            .unwrap();
+                            term.read_char().unwrap();
+                        }
+                    }
+                }
+
+                term.write_line("Press return to continue.").unwrap();
+                term.read_line().unwrap();
+            }
+            'r' => typed_quiz(
+                &term,
+                &db,
+                &mut book,
+                &log,
+                &settings,
+                file_name,
+                QuizMode::Reading,
+            ),
+            'n' => typed_quiz(
+                &term,
+                &db,
+                &mut book,
+                &log,
+                &settings,
+                file_name,
+                QuizMode::Nanori,
+            ),
+            'b' => {
+                let leitner = &settings.leitner;
+                let now = scheduler::now();
+                let counts = book.leitner_counts(leitner, now);
+                for (i, (total, due)) in counts.iter().enumerate() {
+                    term.write_line(&format!(
+                        "box {} (every {} days): {} cards, {} due",
+                        i + 1,
+                        leitner.intervals_days.get(i).copied().unwrap_or(1),
+                        total,
+                        due
+                    ))
+                    .unwrap();
+                }
+
+                let session = book.leitner_due(leitner, now, |k| {
+                    db.matches_filter(k, &settings.quiz_filter)
+                });
+                if session.is_empty() {
+                    term.write_line("No box is due right now. Press any key to continue.")
+                        .unwrap();
+                    term.read_char().unwrap();
+                    continue;
+                }
+
+                term.write_line(&format!(
+                    "Start a session over {} due cards? [y/N]",
+                    session.len()
+                ))
+                .unwrap();
+                if !term.read_char().unwrap().eq_ignore_ascii_case(&'y') {
+                    continue;
+                }
+
+                for quiz in session {
+                    term.clear_screen().unwrap();
+                    term.write_line(&format!(
+                        "box {}",
+                        leitner.box_of(book.kanjis[&quiz].confidence_level)
+                    ))
+                    .unwrap();
+
+                    let asked_at = std::time::Instant::now();
+                    let grade = match ask_meaning(&term, &db, quiz, &settings) {
+                        Some(grade) => grade,
+                        None => break,
+                    };
+
+                    let record = book.grade_leitner(
+                        quiz,
+                        grade,
+                        scheduler::now(),
+                        asked_at.elapsed().as_millis() as u64,
+                    );
+                    log.append(&record);
+                    book.save(file_name);
+                }
+            }
+            'c' => {
+                settings.scheduler = match settings.scheduler {
+                    SchedulerKind::Sm2 => SchedulerKind::Fsrs,
+                    SchedulerKind::Fsrs => SchedulerKind::Sm2,
+                };
+                settings.save(settings_file_name);
+                book.set_scheduler(settings.make_scheduler());
+
+                term.write_line(&format!(
+                    "Reviews are now scheduled with {:?}. Press any key to continue.",
+                    settings.scheduler
+                ))
+                .unwrap();
+                term.read_char().unwrap();
+            }
+            'i' => typed_quiz(
+                &term,
+                &db,
+                &mut book,
+                &log,
+                &settings,
+                file_name,
+                QuizMode::Radical,
+            ),
+            'k' => {
+                term.write_line(
+                    "Which radical? Its number, glyph or name, e.g. '85', '水' or 'water':",
+                )
+                .unwrap();
+                match database::find_radical(&term.read_line().unwrap()) {
+                    Some(radical) => {
+                        let kanjis = db.by_radical(radical.number);
+                        term.write_line(&format!(
+                            "{} kanjis under {}:",
+                            kanjis.len(),
+                            radical.pretty_print()
+                        ))
+                        .unwrap();
+                        for literal in kanjis {
+                            let k = db.kanji(literal).expect("Has to have this entry.");
+                            let known = if book.kanjis.contains_key(&literal) {
+                                " (known)"
+                            } else {
+                                ""
+                            };
+                            term.write_line(&format!(
+                                "{} {:?}{}",
+                                literal,
+                                k.meaning(&settings.languages),
+                                known
+                            ))
+                            .unwrap();
+                        }
+                    }
+                    None => term.write_line("There is no such radical.").unwrap(),
+                }
+
+                term.write_line("Press any key to continue.").unwrap();
+                term.read_char().unwrap();
+            }
+            't' => stroke_type_drill(&term, &db, &book, &settings.render),
+            'v' => {
+                settings.render.view = match settings.render.view {
+                    StrokeView::Static => StrokeView::Animated,
+                    StrokeView::Animated => StrokeView::Filmstrip,
+                    StrokeView::Filmstrip => StrokeView::Terminal,
+                    StrokeView::Terminal => StrokeView::Static,
+                };
+                settings.save(settings_file_name);
+
+                term.write_line(&format!(
+                    "Strokes are shown {:?} now. Press any key to continue.",
+                    settings.render.view
+                ))
+                .unwrap();
+                term.read_char().unwrap();
+            }
+            'w' => {
+                settings.render.direction_markers = !settings.render.direction_markers;
+                settings.save(settings_file_name);
+
+                term.write_line(&format!(
+                    "Direction arrows are {} now. Press any key to continue.",
+                    if settings.render.direction_markers {
+                        "on"
+                    } else {
+                        "off"
+                    }
+                ))
+                .unwrap();
+                term.read_char().unwrap();
+            }
+            'p' => {
+                // custom colors only come from settings.json, cycling never throws them away.
+                let next = match settings.render.palette {
+                    Palette::Classic => Some(Palette::ColorblindSafe),
+                    Palette::ColorblindSafe => Some(Palette::Monochrome),
+                    Palette::Monochrome => Some(Palette::Classic),
+                    Palette::Custom(_) => None,
+                };
+                match next {
+                    Some(palette) => {
+                        settings.render.palette = palette;
+                        settings.save(settings_file_name);
+                        term.write_line(&format!(
+                            "Strokes are colored {:?} now. Press any key to continue.",
+                            settings.render.palette
+                        ))
+                    }
+                    None => term.write_line(
+                        "Strokes use custom colors, change the palette in settings.json to cycle again. Press any key to continue.",
+                    ),
+                }
+                .unwrap();
+                term.read_char().unwrap();
+            }
+            'd' => {
+                term.write_line("Which kanji should be broken down into its parts?")
+                    .unwrap();
+                let input = term.read_line().unwrap();
+                let mut drilled: Vec<kanji_strokes::Component> = match input
+                    .trim()
+                    .chars()
+                    .next()
+                    .and_then(|literal| db.decompositions.get(&literal))
+                {
+                    Some(component) => vec![component.clone()],
+                    None => {
+                        term.write_line(
+                            "KanjiVG does not know that one. Press any key to continue.",
+                        )
+                        .unwrap();
+                        term.read_char().unwrap();
+                        continue;
+                    }
+                };
+
+                while let Some(current) = drilled.last() {
+                    term.clear_screen().unwrap();
+                    let trail: Vec<String> = drilled
+                        .iter()
+                        .map(|c| c.element.clone().unwrap_or_else(|| "?".to_string()))
+                        .collect();
+                    term.write_line(&trail.join(" > ")).unwrap();
+                    term.write_line(&describe_component(&db, &settings, current))
+                        .unwrap();
+                    for (i, child) in current.children.iter().enumerate() {
+                        term.write_line(&format!(
+                            "  [{}] {}",
+                            i,
+                            describe_component(&db, &settings, child)
+                        ))
+                        .unwrap();
+                    }
+                    term.write_line(
+                        "Pick a part to break it down further, [b]ack up, anything else stops.",
+                    )
+                    .unwrap();
+
+                    let key = term.read_char().unwrap();
+                    if key.eq_ignore_ascii_case(&'b') {
+                        drilled.pop();
+                        continue;
+                    }
+                    let child = match key
+                        .to_digit(10)
+                        .and_then(|i| current.children.get(i as usize))
+                    {
+                        Some(child) => child,
+                        None => break,
+                    };
+                    // a part drawn in one go gets broken down by its own KanjiVG entry.
+                    let next = if child.children.is_empty() {
+                        child
+                            .element
+                            .as_ref()
+                            .and_then(|e| e.chars().next())
+                            .and_then(|e| db.decompositions.get(&e))
+                            .filter(|d| !d.children.is_empty())
+                            .cloned()
                     } else {
-                        term.write_line("Skipping addition.");
+                        Some(child.clone())
+                    };
+                    match next {
+                        Some(next) => drilled.push(next),
+                        None => {
+                            term.write_line("That part cannot be broken down any further. Press any key to continue.")
+                                .unwrap();
+                            term.read_char().unwrap();
+                        }
                     }
                 }
+            }
+            'g' => {
+                term.write_line(&format!(
+                    "Meanings are shown in: {}. Type in preferred languages in order, e.g. 'es fr' (en, fr, es and pt are there, English is the fallback):",
+                    settings.languages.join(" ")
+                ))
+                .unwrap();
+                let languages: Vec<String> = term
+                    .read_line()
+                    .unwrap()
+                    .split_whitespace()
+                    .map(|l| l.to_lowercase())
+                    .collect();
+                if !languages.is_empty() {
+                    settings.languages = languages;
+                    settings.save(settings_file_name);
+                }
 
-                term.write_line("Press return to continue.");
-                term.read_line();
+                term.write_line(&format!(
+                    "Meanings are shown in: {}. Press any key to continue.",
+                    settings.languages.join(" ")
+                ))
+                .unwrap();
+                term.read_char().unwrap();
+            }
+            'e' => {
+                // shown and searched together, so no search finds a kanji by a reading it doesnt show.
+                let foreign = !settings.show_foreign_readings;
+                settings.show_foreign_readings = foreign;
+                settings.search.foreign_readings = foreign;
+                settings.save(settings_file_name);
+
+                term.write_line(&format!(
+                    "Chinese, korean and vietnamese readings are {} now. Press any key to continue.",
+                    if foreign { "shown and searched" } else { "off" }
+                ))
+                .unwrap();
+                term.read_char().unwrap();
+            }
+            'h' => {
+                settings.search.include_nanori = !settings.search.include_nanori;
+                settings.save(settings_file_name);
+
+                term.write_line(&format!(
+                    "Reading search {} name readings now. Press any key to continue.",
+                    if settings.search.include_nanori {
+                        "looks up"
+                    } else {
+                        "leaves out"
+                    }
+                ))
+                .unwrap();
+                term.read_char().unwrap();
             }
             'l' => {
                 for (counter, (_, kanji)) in book.kanjis.iter().enumerate() {
-                    term.write_line(&format!("[{}] {:?}", counter, kanji.kanji));
+                    term.write_line(&format!("[{}] {:?}", counter, kanji.kanji))
+                        .unwrap();
                 }
 
-                term.write_line("Press any key to continue.");
-                term.read_char();
+                term.write_line("Press any key to continue.").unwrap();
+                term.read_char().unwrap();
             }
             's' => {
-                term.write_line("Type in pattern by which you want to search");
+                term.write_line("Type in pattern by which you want to search")
+                    .unwrap();
                 let pattern = term.read_line().expect("char was supposed to be here!");
-                let result = db.find(&pattern);
+                let result = db.find(&pattern, &settings.search, &settings.languages);
                 if result.is_empty() {
                     term.write_line(&format!(
                         "pattern you've put in: {} does not exist in db.",
                         pattern
-                    ));
+                    ))
+                    .unwrap();
                     continue;
                 }
                 //meh we need to handle multiple prints but for now lets just take first one.
 
                 if let Some(single_result) =
-                    ask_user_to_select_one_from_result(&term, &pattern, &result)
+                    ask_user_to_select_one_from_result(&term, &pattern, &result, &settings)
                 {
                     let strokes = &single_result.1;
-                    show_strokes(&term, single_result.0.literal, strokes);
+                    show_strokes(&term, single_result.0.literal, strokes, &settings.render);
                 }
-                term.write_line("Press any key to continue.");
-                term.read_char();
+                term.write_line("Press any key to continue.").unwrap();
+                term.read_char().unwrap();
                 continue;
             }
             _ => {
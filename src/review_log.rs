@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::book::QuizMode;
+use crate::scheduler::{Grade, ReviewState};
+
+// a single graded answer, the log is a sequence of those one json per line.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReviewRecord {
+    pub kanji: char,
+    pub timestamp: u64,
+    pub mode: QuizMode,
+    pub grade: Grade,
+    pub response_ms: u64,
+    pub before: ReviewState,
+    pub after: ReviewState,
+    // answered in a leitner session, only the box moved.
+    #[serde(default)]
+    pub leitner: bool,
+}
+
+pub struct ReviewLog {
+    file_name: String,
+}
+
+impl ReviewLog {
+    pub fn new(file_name: &str) -> Self {
+        ReviewLog {
+            file_name: file_name.to_string(),
+        }
+    }
+
+    pub fn append(&self, record: &ReviewRecord) {
+        let serialized = serde_json::to_string(record).expect("Unable to serialize review!");
+        let mut f = std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.file_name)
+            .expect("Couldnt open review log for appending.");
+
+        writeln!(f, "{}", serialized).expect("Couldnt write to the review log.");
+    }
+
+    pub fn load(&self) -> Vec<ReviewRecord> {
+        if !Path::new(&self.file_name).exists() {
+            return Vec::new();
+        }
+
+        let f = std::fs::File::open(&self.file_name).expect("Couldnt open review log");
+        BufReader::new(f)
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i, line.expect("Couldnt read review log")))
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(&line).unwrap_or_else(|e| {
+                    panic!(
+                        "Broken review log line {} in {}: {}",
+                        i + 1,
+                        self.file_name,
+                        e
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+// chronological (timestamp, grade) answers of every card, what scheduler fitting works on.
+pub fn histories(records: &[ReviewRecord]) -> Vec<Vec<(u64, Grade)>> {
+    let mut by_card: BTreeMap<(char, QuizMode), Vec<(u64, Grade)>> = BTreeMap::new();
+    for r in records.iter().filter(|r| !r.leitner) {
+        by_card
+            .entry((r.kanji, r.mode))
+            .or_default()
+            .push((r.timestamp, r.grade));
+    }
+
+    by_card
+        .into_values()
+        .map(|mut history| {
+            history.sort_by_key(|(timestamp, _)| *timestamp);
+            history
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod review_log_test {
+    use super::*;
+
+    #[test]
+    fn append_and_load_back() {
+        let file_name = std::env::temp_dir().join(format!(
+            "kanji-review-log-test-{}.jsonl",
+            std::process::id()
+        ));
+        let file_name = file_name.to_str().unwrap();
+        let log = ReviewLog::new(file_name);
+        assert!(log.load().is_empty());
+
+        let record = |kanji, timestamp, grade| ReviewRecord {
+            kanji,
+            timestamp,
+            mode: QuizMode::Meaning,
+            grade,
+            response_ms: 1200,
+            before: ReviewState::default(),
+            after: ReviewState::default(),
+            leitner: false,
+        };
+        log.append(&record('二', 20, Grade::Good));
+        log.append(&record('一', 10, Grade::Again));
+        log.append(&record('二', 5, Grade::Hard));
+
+        let loaded = log.load();
+        std::fs::remove_file(file_name).unwrap();
+
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded[0], record('二', 20, Grade::Good));
+        assert_eq!(
+            histories(&loaded),
+            vec![
+                vec![(10, Grade::Again)],
+                vec![(5, Grade::Hard), (20, Grade::Good)]
+            ]
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use super::{Grade, ReviewState, Scheduler, RELEARN_DELAY_SECONDS, SECONDS_PER_DAY};
+
+// FSRS-4.5, see https://github.com/open-spaced-repetition/fsrs4anki/wiki/The-Algorithm
+pub const DEFAULT_PARAMETERS: [f64; 17] = [
+    0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367, 1.0461, 2.1072,
+    0.0793, 0.3246, 1.587, 0.2272, 2.8755,
+];
+pub const DEFAULT_RETENTION: f64 = 0.9;
+
+const DECAY: f64 = -0.5;
+const FACTOR: f64 = 19.0 / 81.0;
+
+// allowed range of every parameter, fitting never leaves it.
+const BOUNDS: [(f64, f64); 17] = [
+    (0.1, 100.0),
+    (0.1, 100.0),
+    (0.1, 100.0),
+    (0.1, 100.0),
+    (1.0, 10.0),
+    (0.001, 4.0),
+    (0.001, 4.0),
+    (0.001, 0.75),
+    (0.0, 4.5),
+    (0.0, 0.8),
+    (0.001, 3.5),
+    (0.001, 5.0),
+    (0.001, 0.25),
+    (0.001, 0.9),
+    (0.0, 4.0),
+    (0.0, 1.0),
+    (1.0, 6.0),
+];
+
+#[derive(Debug, Clone)]
+pub struct Fsrs {
+    pub parameters: [f64; 17],
+    pub desired_retention: f64,
+}
+
+impl Default for Fsrs {
+    fn default() -> Self {
+        Fsrs {
+            parameters: DEFAULT_PARAMETERS,
+            desired_retention: DEFAULT_RETENTION,
+        }
+    }
+}
+
+// FSRS rates answers 1 (again) - 4 (easy), the quiz has no 'easy' answer.
+fn rating(grade: Grade) -> f64 {
+    match grade {
+        Grade::Again => 1.0,
+        Grade::Hard => 2.0,
+        Grade::Good => 3.0,
+    }
+}
+
+pub fn retrievability(elapsed_days: f64, stability: f64) -> f64 {
+    (1.0 + FACTOR * elapsed_days / stability).powf(DECAY)
+}
+
+impl Fsrs {
+    fn initial_stability(&self, grade: Grade) -> f64 {
+        self.parameters[rating(grade) as usize - 1].max(0.1)
+    }
+
+    fn initial_difficulty(&self, rating: f64) -> f64 {
+        let w = &self.parameters;
+        (w[4] - (rating - 3.0) * w[5]).clamp(1.0, 10.0)
+    }
+
+    fn next_difficulty(&self, difficulty: f64, grade: Grade) -> f64 {
+        let w = &self.parameters;
+        let changed = difficulty - w[6] * (rating(grade) - 3.0);
+        // mean reversion towards the difficulty of an 'easy' first answer.
+        (w[7] * self.initial_difficulty(4.0) + (1.0 - w[7]) * changed).clamp(1.0, 10.0)
+    }
+
+    fn next_stability(&self, difficulty: f64, stability: f64, r: f64, grade: Grade) -> f64 {
+        let w = &self.parameters;
+        if grade == Grade::Again {
+            let forgotten = w[11]
+                * difficulty.powf(-w[12])
+                * ((stability + 1.0).powf(w[13]) - 1.0)
+                * (w[14] * (1.0 - r)).exp();
+            return forgotten.min(stability).max(0.1);
+        }
+
+        let hard_penalty = if grade == Grade::Hard { w[15] } else { 1.0 };
+        stability
+            * (1.0
+                + w[8].exp()
+                    * (11.0 - difficulty)
+                    * stability.powf(-w[9])
+                    * ((w[10] * (1.0 - r)).exp() - 1.0)
+                    * hard_penalty)
+    }
+
+    // (stability, difficulty) after answering, memory state of a new card is None.
+    fn next_memory(
+        &self,
+        memory: Option<(f64, f64)>,
+        elapsed_days: f64,
+        grade: Grade,
+    ) -> (f64, f64) {
+        match memory {
+            None => (
+                self.initial_stability(grade),
+                self.initial_difficulty(rating(grade)),
+            ),
+            Some((stability, difficulty)) => {
+                let r = retrievability(elapsed_days, stability);
+                (
+                    self.next_stability(difficulty, stability, r, grade),
+                    self.next_difficulty(difficulty, grade),
+                )
+            }
+        }
+    }
+
+    fn interval_days(&self, stability: f64) -> f64 {
+        (stability / FACTOR * (self.desired_retention.powf(1.0 / DECAY) - 1.0))
+            .round()
+            .max(1.0)
+    }
+}
+
+impl Scheduler for Fsrs {
+    fn review(&self, state: &ReviewState, grade: Grade, now: u64) -> ReviewState {
+        // cards scheduled so far by SM-2 have no memory state yet and start over as new ones.
+        let memory = match state.last_review {
+            Some(_) if state.stability > 0.0 => Some((state.stability, state.difficulty)),
+            _ => None,
+        };
+        let elapsed_days = state
+            .last_review
+            .map_or(0.0, |last| now.saturating_sub(last) as f64)
+            / SECONDS_PER_DAY as f64;
+        let (stability, difficulty) = self.next_memory(memory, elapsed_days, grade);
+
+        let (interval_days, due) = if grade == Grade::Again {
+            (0.0, now + RELEARN_DELAY_SECONDS)
+        } else {
+            let interval_days = self.interval_days(stability);
+            (
+                interval_days,
+                now + (interval_days * SECONDS_PER_DAY as f64) as u64,
+            )
+        };
+
+        ReviewState {
+            interval_days,
+            repetitions: if grade == Grade::Again {
+                0
+            } else {
+                state.repetitions + 1
+            },
+            due,
+            stability,
+            difficulty,
+            last_review: Some(now),
+            ..state.clone()
+        }
+    }
+}
+
+// log loss of predicted recall over every review that had a previous one to predict from.
+fn loss(fsrs: &Fsrs, histories: &[Vec<(u64, Grade)>]) -> f64 {
+    let mut loss = 0.0;
+    for history in histories {
+        let mut memory = None;
+        let mut last_review = None;
+        for &(timestamp, grade) in history {
+            let elapsed_days = last_review
+                .map_or(0.0, |last| timestamp.saturating_sub(last) as f64)
+                / SECONDS_PER_DAY as f64;
+            if let Some((stability, _)) = memory {
+                let r = retrievability(elapsed_days, stability).clamp(0.0001, 0.9999);
+                loss -= if grade == Grade::Again {
+                    (1.0 - r).ln()
+                } else {
+                    r.ln()
+                };
+            }
+            memory = Some(fsrs.next_memory(memory, elapsed_days, grade));
+            last_review = Some(timestamp);
+        }
+    }
+    loss
+}
+
+// Refits the parameters to the given review histories (chronological (timestamp, grade) per card)
+// using resilient backpropagation over numerical gradients. None if there is nothing to learn from.
+pub fn fit_parameters(initial: &[f64; 17], histories: &[Vec<(u64, Grade)>]) -> Option<[f64; 17]> {
+    if histories.iter().all(|h| h.len() < 2) {
+        return None;
+    }
+
+    let mut fsrs = Fsrs {
+        parameters: *initial,
+        desired_retention: DEFAULT_RETENTION,
+    };
+    let mut steps: Vec<f64> = BOUNDS.iter().map(|(lo, hi)| (hi - lo) * 0.01).collect();
+    let mut previous_gradient = [0.0; 17];
+
+    for _ in 0..200 {
+        let mut gradient = [0.0; 17];
+        for i in 0..17 {
+            let h = (BOUNDS[i].1 - BOUNDS[i].0) * 1e-5;
+            let original = fsrs.parameters[i];
+            fsrs.parameters[i] = (original + h).min(BOUNDS[i].1);
+            let up = loss(&fsrs, histories);
+            let up_delta = fsrs.parameters[i] - original;
+            fsrs.parameters[i] = (original - h).max(BOUNDS[i].0);
+            let down = loss(&fsrs, histories);
+            let down_delta = original - fsrs.parameters[i];
+            fsrs.parameters[i] = original;
+            gradient[i] = (up - down) / (up_delta + down_delta);
+        }
+
+        for i in 0..17 {
+            let direction = gradient[i] * previous_gradient[i];
+            if direction > 0.0 {
+                steps[i] *= 1.2;
+            } else if direction < 0.0 {
+                steps[i] *= 0.5;
+            }
+            if gradient[i] != 0.0 {
+                fsrs.parameters[i] = (fsrs.parameters[i] - gradient[i].signum() * steps[i])
+                    .clamp(BOUNDS[i].0, BOUNDS[i].1);
+            }
+            previous_gradient[i] = gradient[i];
+        }
+    }
+
+    Some(fsrs.parameters)
+}
+
+#[cfg(test)]
+mod fsrs_test {
+    use super::*;
+
+    #[test]
+    fn stability_grows_on_success_and_drops_on_failure() {
+        let fsrs = Fsrs::default();
+        let now = 1_000_000;
+        let first = fsrs.review(&ReviewState::default(), Grade::Good, now);
+        assert_eq!(first.stability, DEFAULT_PARAMETERS[2]);
+        assert_eq!(first.last_review, Some(now));
+        assert!(first.due > now);
+
+        let later = first.due;
+        let second = fsrs.review(&first, Grade::Good, later);
+        assert!(second.stability > first.stability);
+        assert!(second.interval_days > first.interval_days);
+
+        let failed = fsrs.review(&second, Grade::Again, second.due);
+        assert!(failed.stability < second.stability);
+        assert!(failed.difficulty > second.difficulty);
+        assert_eq!(failed.due, second.due + RELEARN_DELAY_SECONDS);
+    }
+
+    #[test]
+    fn fitting_lowers_the_loss() {
+        let day = SECONDS_PER_DAY;
+        // someone who never forgets anything, default parameters are too pessimistic for them.
+        let histories: Vec<Vec<(u64, Grade)>> = (0..20)
+            .map(|i| {
+                vec![
+                    (i * 100, Grade::Good),
+                    (i * 100 + 5 * day, Grade::Good),
+                    (i * 100 + 40 * day, Grade::Good),
+                    (i * 100 + 200 * day, Grade::Good),
+                ]
+            })
+            .collect();
+
+        let fitted = fit_parameters(&DEFAULT_PARAMETERS, &histories).unwrap();
+        let before = loss(&Fsrs::default(), &histories);
+        let after = loss(
+            &Fsrs {
+                parameters: fitted,
+                ..Fsrs::default()
+            },
+            &histories,
+        );
+        assert!(after < before);
+        assert!(fit_parameters(&DEFAULT_PARAMETERS, &[vec![(0, Grade::Good)]]).is_none());
+    }
+}
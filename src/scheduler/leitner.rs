@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use super::SECONDS_PER_DAY;
+
+// Leitner boxes on top of Entry::confidence_level, box 1 holds new and failed cards,
+// every known answer moves the card a box up until the last one.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Leitner {
+    // how many days a card rests in each box before it is due again.
+    pub intervals_days: Vec<u64>,
+}
+
+impl Default for Leitner {
+    fn default() -> Self {
+        Leitner {
+            intervals_days: vec![1, 2, 4, 8, 16, 32],
+        }
+    }
+}
+
+impl Leitner {
+    pub fn box_count(&self) -> usize {
+        self.intervals_days.len().max(1)
+    }
+
+    // 1 based box number.
+    pub fn box_of(&self, confidence_level: i32) -> usize {
+        (confidence_level.max(0) as usize).min(self.box_count() - 1) + 1
+    }
+
+    pub fn is_due(&self, confidence_level: i32, last_review: Option<u64>, now: u64) -> bool {
+        match last_review {
+            None => true,
+            Some(last) => {
+                let interval = self
+                    .intervals_days
+                    .get(self.box_of(confidence_level) - 1)
+                    .copied()
+                    .unwrap_or(1);
+                last + interval * SECONDS_PER_DAY <= now
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod leitner_test {
+    use super::*;
+
+    #[test]
+    fn boxes_follow_confidence() {
+        let leitner = Leitner {
+            intervals_days: vec![1, 3, 7],
+        };
+        assert_eq!(leitner.box_of(-1), 1);
+        assert_eq!(leitner.box_of(0), 1);
+        assert_eq!(leitner.box_of(2), 3);
+        assert_eq!(leitner.box_of(7), 3);
+
+        let now = 100 * SECONDS_PER_DAY;
+        assert!(leitner.is_due(0, None, now));
+        assert!(leitner.is_due(0, Some(now - SECONDS_PER_DAY), now));
+        assert!(!leitner.is_due(1, Some(now - SECONDS_PER_DAY), now));
+        assert!(leitner.is_due(7, Some(now - 7 * SECONDS_PER_DAY), now));
+    }
+}
@@ -0,0 +1,75 @@
+mod fsrs;
+mod leitner;
+mod sm2;
+
+pub use fsrs::*;
+pub use leitner::*;
+pub use sm2::*;
+
+use serde::{Deserialize, Serialize};
+
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+// failed cards come back within the same session instead of waiting a whole day.
+pub const RELEARN_DELAY_SECONDS: u64 = 10 * 60;
+
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system clock is set before 1970?")
+        .as_secs()
+}
+
+// answers as given in the quiz: [1] you know it, [2] not confident, [3] fail.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grade {
+    Good,
+    Hard,
+    Again,
+}
+
+// scheduling state of a single card, entries without it (older dict.json) start as new cards due right away.
+// every scheduler keeps its own fields in here, so switching between them does not change the stored entries.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ReviewState {
+    // SM-2
+    pub ease: f64,
+    // shared
+    pub interval_days: f64,
+    pub repetitions: u32,
+    pub due: u64,
+    pub last_review: Option<u64>,
+    // FSRS
+    pub stability: f64,
+    pub difficulty: f64,
+}
+
+impl Default for ReviewState {
+    fn default() -> Self {
+        ReviewState {
+            ease: sm2::INITIAL_EASE,
+            interval_days: 0.0,
+            repetitions: 0,
+            due: 0,
+            last_review: None,
+            stability: 0.0,
+            difficulty: 0.0,
+        }
+    }
+}
+
+impl ReviewState {
+    pub fn is_due(&self, now: u64) -> bool {
+        self.due <= now
+    }
+}
+
+pub trait Scheduler {
+    fn review(&self, state: &ReviewState, grade: Grade, now: u64) -> ReviewState;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedulerKind {
+    Sm2,
+    Fsrs,
+}
@@ -0,0 +1,77 @@
+use super::{Grade, ReviewState, Scheduler, RELEARN_DELAY_SECONDS, SECONDS_PER_DAY};
+
+pub const INITIAL_EASE: f64 = 2.5;
+const MINIMAL_EASE: f64 = 1.3;
+
+#[derive(Debug, Clone, Default)]
+pub struct Sm2;
+
+// SM-2 works with answer quality in 0-5 range, anything below 3 is a lapse.
+fn quality(grade: Grade) -> f64 {
+    match grade {
+        Grade::Good => 5.0,
+        Grade::Hard => 3.0,
+        Grade::Again => 1.0,
+    }
+}
+
+impl Scheduler for Sm2 {
+    fn review(&self, state: &ReviewState, grade: Grade, now: u64) -> ReviewState {
+        let q = quality(grade);
+        let ease = (state.ease + (0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02))).max(MINIMAL_EASE);
+
+        if grade == Grade::Again {
+            return ReviewState {
+                ease,
+                interval_days: 0.0,
+                repetitions: 0,
+                due: now + RELEARN_DELAY_SECONDS,
+                last_review: Some(now),
+                ..state.clone()
+            };
+        }
+
+        let interval_days = match state.repetitions {
+            0 => 1.0,
+            1 => 6.0,
+            _ => (state.interval_days * state.ease).round(),
+        };
+
+        ReviewState {
+            ease,
+            interval_days,
+            repetitions: state.repetitions + 1,
+            due: now + (interval_days * SECONDS_PER_DAY as f64) as u64,
+            last_review: Some(now),
+            ..state.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod sm2_test {
+    use super::*;
+
+    #[test]
+    fn intervals_grow_and_reset_on_failure() {
+        let now = 1_000_000;
+        let first = Sm2.review(&ReviewState::default(), Grade::Good, now);
+        assert_eq!(first.interval_days, 1.0);
+        assert_eq!(first.due, now + SECONDS_PER_DAY);
+
+        let second = Sm2.review(&first, Grade::Good, now);
+        assert_eq!(second.interval_days, 6.0);
+
+        let third = Sm2.review(&second, Grade::Good, now);
+        assert_eq!(third.repetitions, 3);
+        assert!(third.interval_days > 6.0);
+
+        let hard = Sm2.review(&third, Grade::Hard, now);
+        assert!(hard.ease < third.ease);
+
+        let failed = Sm2.review(&hard, Grade::Again, now);
+        assert_eq!(failed.repetitions, 0);
+        assert_eq!(failed.due, now + RELEARN_DELAY_SECONDS);
+        assert!(failed.ease >= MINIMAL_EASE);
+    }
+}
@@ -0,0 +1,85 @@
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::{KanjiFilter, SearchOptions, ENGLISH};
+use crate::kanji_strokes::RenderOptions;
+use crate::scheduler::{self, Fsrs, Leitner, Scheduler, SchedulerKind, Sm2};
+
+// user tweakable knobs, kept in settings.json next to dict.json. Missing keys fall back to defaults.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub scheduler: SchedulerKind,
+    // None means the FSRS defaults, refitted ones are stored here.
+    pub fsrs_parameters: Option<[f64; 17]>,
+    pub desired_retention: f64,
+    pub leitner: Leitner,
+    pub search: SearchOptions,
+    // quizzes only ask about kanjis matching this one, e.g. a single JLPT level.
+    pub quiz_filter: KanjiFilter,
+    // preferred meaning languages in order, e.g. ["es", "fr"], English is the fallback.
+    pub languages: Vec<String>,
+    // show chinese, korean and vietnamese readings along with the japanese ones.
+    pub show_foreign_readings: bool,
+    pub render: RenderOptions,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            scheduler: SchedulerKind::Sm2,
+            fsrs_parameters: None,
+            desired_retention: scheduler::DEFAULT_RETENTION,
+            leitner: Leitner::default(),
+            search: SearchOptions::default(),
+            quiz_filter: KanjiFilter::default(),
+            languages: vec![ENGLISH.to_string()],
+            show_foreign_readings: false,
+            render: RenderOptions::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(file_name: &str) -> Self {
+        if Path::new(file_name).exists() {
+            serde_json::from_reader(std::fs::File::open(file_name).expect("Couldnt open file"))
+                .unwrap_or_else(|e| {
+                    panic!("Unable to parse out settings from {}: {}", file_name, e)
+                })
+        } else {
+            Settings::default()
+        }
+    }
+
+    pub fn save(&self, file_name: &str) {
+        let serialized = serde_json::to_string_pretty(self).expect("Unable to serialize settings!");
+        let mut f = std::fs::OpenOptions::new()
+            .truncate(true)
+            .write(true)
+            .create(true)
+            .open(file_name)
+            .expect("Couldnt open file for writing.");
+
+        f.write_all(serialized.as_bytes())
+            .expect("Couldnt write the settings.");
+    }
+
+    pub fn fsrs(&self) -> Fsrs {
+        Fsrs {
+            parameters: self
+                .fsrs_parameters
+                .unwrap_or(scheduler::DEFAULT_PARAMETERS),
+            desired_retention: self.desired_retention,
+        }
+    }
+
+    pub fn make_scheduler(&self) -> Box<dyn Scheduler> {
+        match self.scheduler {
+            SchedulerKind::Sm2 => Box::new(Sm2),
+            SchedulerKind::Fsrs => Box::new(self.fsrs()),
+        }
+    }
+}